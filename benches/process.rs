@@ -0,0 +1,55 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use nih_plug::prelude::*;
+use one_pole::OnePoleFilter;
+
+const SAMPLE_RATE: f32 = 44100.;
+const NUM_SAMPLES: usize = 512;
+
+fn param(plugin: &OnePoleFilter, id: &str) -> ParamPtr {
+    plugin
+        .params()
+        .param_map()
+        .into_iter()
+        .find_map(|(param_id, ptr, _)| (param_id == id).then_some(ptr))
+        .unwrap_or_else(|| panic!("no parameter with id {id}"))
+}
+
+/// Times a 512-sample stereo block with each filter mode. The throughput is reported
+/// in samples (per channel) per second, the time per sample being its inverse
+fn process(c: &mut Criterion) {
+    let mut group = c.benchmark_group("process");
+    group.throughput(Throughput::Elements(NUM_SAMPLES as u64));
+
+    let mode = param(&OnePoleFilter::default(), "mode");
+    // SAFETY: nothing else is touching the parameters
+    let num_modes = unsafe { mode.step_count() }.unwrap() + 1;
+
+    for i in 0..num_modes {
+        let mut plugin = OnePoleFilter::default();
+        let mode = param(&plugin, "mode");
+        let normalized = i as f32 / (num_modes - 1) as f32;
+        // SAFETY: same as above
+        let name = unsafe {
+            mode.set_normalized_value(normalized);
+            mode.normalized_value_to_string(normalized, false)
+        };
+        plugin.prepare(SAMPLE_RATE);
+
+        // white-ish noise, from a cheap LCG
+        let mut seed = 1u32;
+        let mut noise = || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as f32 / (1 << 23) as f32 - 1.
+        };
+        let mut samples: Vec<[f32; 2]> = (0..NUM_SAMPLES).map(|_| [noise(), noise()]).collect();
+
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| plugin.process_block(&mut samples))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, process);
+criterion_main!(benches);
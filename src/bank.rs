@@ -0,0 +1,115 @@
+use nih_plug::prelude::*;
+
+use std::{
+    env, fs, io,
+    path::PathBuf,
+    sync::{Mutex, MutexGuard},
+};
+
+use crate::presets::Preset;
+
+/// User presets, shared by all instances of the plugin loaded in the same process,
+/// mirroring the bank file. Only ever touched by the editor and background tasks,
+/// never by `process`
+static USER_PRESETS: Mutex<Vec<Preset>> = Mutex::new(Vec::new());
+
+/// Held throughout each access to the bank file, so that concurrent saves from several
+/// instances don't overwrite each other's presets, and that loads don't swap in what
+/// a save is replacing. `USER_PRESETS` is only locked to swap the new list in, the
+/// editor, drawing it, never waits on the disk
+static BANK_FILE: Mutex<()> = Mutex::new(());
+
+/// Disk operations on the user preset bank, run on nih-plug's background thread
+pub enum PresetTask {
+    /// Reads the bank file into the user presets
+    Load,
+    /// Adds a preset to the bank, replacing the one with the same name, if any
+    Save(Preset),
+}
+
+pub fn user_presets() -> MutexGuard<'static, Vec<Preset>> {
+    // a panic while holding the lock leaves the list usable
+    USER_PRESETS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn run(task: PresetTask) {
+    let _bank_file = BANK_FILE.lock().unwrap_or_else(|e| e.into_inner());
+    let result = match task {
+        PresetTask::Load => load().map(|presets| *user_presets() = presets),
+        PresetTask::Save(preset) => save(preset),
+    };
+
+    if let Err(e) = result {
+        nih_log!("couldn't access the preset bank: {e}");
+    }
+}
+
+/// `presets.tsv`, in this plugin's folder of the platform's configuration directory
+fn bank_path() -> Option<PathBuf> {
+    let home = || env::var_os("HOME").map(PathBuf::from);
+    let config = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home().map(|home| home.join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home().map(|home| home.join(".config")))
+    };
+    Some(config?.join("one_pole").join("presets.tsv"))
+}
+
+fn no_bank_path() -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, "no configuration directory")
+}
+
+/// Reads the bank file, one preset per line, skipping comments, starting with `#`,
+/// and invalid lines. A missing file is an empty bank
+fn load() -> io::Result<Vec<Preset>> {
+    let contents = match fs::read_to_string(bank_path().ok_or_else(no_bank_path)?) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    Ok(contents
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let preset = Preset::from_line(line);
+            if preset.is_none() {
+                nih_log!("skipping invalid preset: {line}");
+            }
+            preset
+        })
+        .collect())
+}
+
+/// Re-reads the bank, in case another process wrote to it, adds `preset`, then writes
+/// it to a temporary file, moved over the bank file, so that it's never seen half written
+fn save(preset: Preset) -> io::Result<()> {
+    let path = bank_path().ok_or_else(no_bank_path)?;
+
+    let mut presets = load()?;
+
+    match presets.iter_mut().find(|p| p.name == preset.name) {
+        Some(existing) => *existing = preset,
+        None => presets.push(preset),
+    }
+
+    let mut contents = format!("# {}\n", Preset::HEADER);
+    for preset in presets.iter() {
+        contents.push_str(&preset.to_line());
+        contents.push('\n');
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let temp = path.with_extension("tsv.tmp");
+    fs::write(&temp, contents)?;
+    fs::rename(temp, path)?;
+
+    *user_presets() = presets;
+    Ok(())
+}
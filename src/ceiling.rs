@@ -0,0 +1,41 @@
+use nih_plug::prelude::*;
+use plugin_util::simd::*;
+
+use crate::soft_clip;
+
+/// How the wet signal is kept under the ceiling
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Ceiling {
+    #[default]
+    Off,
+    /// Brickwall clipping, signals below the ceiling are left untouched
+    Hard,
+    /// Gradually bends peaks towards the ceiling, from 6 dB below it,
+    /// signals below that are left untouched
+    Soft,
+    /// Limits the signal's true peak level, between samples too, see
+    /// [`TruePeakLimiter`](crate::true_peak::TruePeakLimiter). Adds latency
+    #[name = "True peak"]
+    TruePeak,
+}
+
+impl Ceiling {
+    /// Keeps `x` within `±ceiling`, `ceiling` being a linear gain. The true peak
+    /// limiter needs its own state, so it's left to the caller
+    pub fn limit(self, x: f32x2, ceiling: f32) -> f32x2 {
+        let c = Simd::splat(ceiling);
+        match self {
+            Self::Off | Self::TruePeak => x,
+            Self::Hard => x.simd_clamp(-c, c),
+            Self::Soft => {
+                let knee = Simd::splat(0.5) * c;
+                let abs = x.abs();
+                // `soft_clip` has a slope of 1 at 0, so the curve joins the
+                // linear part smoothly, and it never quite reaches the ceiling
+                let over = (abs - knee).simd_max(Simd::splat(0.));
+                let bent = knee + (c - knee) * soft_clip(over / (c - knee));
+                abs.simd_le(knee).select(x, bent.copysign(x))
+            }
+        }
+    }
+}
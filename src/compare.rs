@@ -0,0 +1,97 @@
+use nih_plug::prelude::*;
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+/// Every parameter's normalized value, by ID
+type Snapshot = Vec<(String, f32)>;
+
+/// A/B comparison: two complete sets of parameter values, switched between from
+/// the editor. The parameters themselves hold the active slot's values, so `process`
+/// never looks in here, the other slot's are kept aside until it's switched to.
+/// Both are saved with the plugin's state.
+///
+/// Only ever touched by the editor, and the host saving, or restoring, the state
+#[derive(Default)]
+pub struct Compare {
+    /// each slot's values, as of when it was last switched away from, empty before that
+    slots: Mutex<[Snapshot; 2]>,
+    /// `0` for A, `1` for B
+    active: AtomicUsize,
+}
+
+impl Compare {
+    fn slots(&self) -> MutexGuard<'_, [Snapshot; 2]> {
+        // a panic while holding the lock leaves the slots usable
+        self.slots.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// `0` for A, `1` for B
+    pub fn active(&self) -> usize {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    /// Keeps the current values of `params` in the active slot, and loads those of
+    /// `slot`. Those of a slot never used before are the current ones. The host is
+    /// notified of every parameter change, so this must be called from the GUI thread
+    pub fn switch(&self, params: &impl Params, setter: &ParamSetter, slot: usize) {
+        let active = self.active();
+        if slot == active {
+            return;
+        }
+
+        let mut slots = self.slots();
+        slots[active] = capture(params);
+        if !slots[slot].is_empty() {
+            load(params, setter, &slots[slot]);
+        }
+        self.active.store(slot, Ordering::Relaxed);
+    }
+
+    /// Copies the current values of `params` to the other slot
+    pub fn copy_to_other(&self, params: &impl Params) {
+        self.slots()[1 - self.active()] = capture(params);
+    }
+}
+
+fn capture(params: &impl Params) -> Snapshot {
+    params
+        .param_map()
+        .into_iter()
+        // SAFETY: the parameters outlive their pointers here
+        .map(|(id, param, _)| (id, unsafe { param.unmodulated_normalized_value() }))
+        .collect()
+}
+
+/// Parameters missing from `snapshot` (e. g. added since it was saved) are left as is
+fn load(params: &impl Params, setter: &ParamSetter, snapshot: &Snapshot) {
+    for (id, param, _) in params.param_map() {
+        let Some(&(_, value)) = snapshot.iter().find(|(saved, _)| *saved == id) else {
+            continue;
+        };
+        // SAFETY: same as above, and this is the GUI thread
+        unsafe {
+            let context = setter.raw_context;
+            context.raw_begin_set_parameter(param);
+            context.raw_set_parameter_normalized(param, value);
+            context.raw_end_set_parameter(param);
+        }
+    }
+}
+
+/// The active slot, and both slots' values
+type SavedSlots = (usize, [Snapshot; 2]);
+
+impl<'a> PersistentField<'a, SavedSlots> for Compare {
+    fn set(&self, (active, slots): SavedSlots) {
+        *self.slots() = slots;
+        self.active.store(active.min(1), Ordering::Relaxed);
+    }
+
+    fn map<F, R>(&self, f: F) -> R
+    where
+        F: Fn(&SavedSlots) -> R,
+    {
+        f(&(self.active(), self.slots().clone()))
+    }
+}
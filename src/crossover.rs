@@ -0,0 +1,94 @@
+use plugin_util::simd::*;
+
+use core::f32::consts::{PI, SQRT_2};
+
+/// Highest crossover frequency, as a fraction of the sample rate, keeping the
+/// prewarping's `tan` away from its pole at Nyquist
+const MAX_FREQ_RATIO: f32 = 0.49;
+
+/// Second order Butterworth filter, as a topology-preserving state variable filter,
+/// with lowpass and highpass outputs. The one-pole stages can't make these, their
+/// poles are real, and a Butterworth's are complex
+#[derive(Default)]
+struct Svf {
+    /// integrators' states
+    ic1eq: f32x2,
+    ic2eq: f32x2,
+}
+
+/// `Svf`'s coefficients
+#[derive(Clone, Copy)]
+struct SvfCoefficients {
+    a1: f32x2,
+    a2: f32x2,
+    a3: f32x2,
+}
+
+/// Damping of a Butterworth response, `1 / Q`
+const K: f32 = SQRT_2;
+
+impl SvfCoefficients {
+    /// For a cutoff of `freq_ratio` times the sample rate
+    fn new(freq_ratio: f32) -> Self {
+        let g = (PI * freq_ratio.min(MAX_FREQ_RATIO)).tan();
+        let a1 = 1. / (1. + g * (g + K));
+        let a2 = g * a1;
+        Self {
+            a1: Simd::splat(a1),
+            a2: Simd::splat(a2),
+            a3: Simd::splat(g * a2),
+        }
+    }
+}
+
+impl Svf {
+    /// Lowpass and highpass outputs, for input `x`
+    fn process(&mut self, c: &SvfCoefficients, x: f32x2) -> (f32x2, f32x2) {
+        let v3 = x - self.ic2eq;
+        let v1 = c.a1 * self.ic1eq + c.a2 * v3;
+        let v2 = self.ic2eq + c.a2 * self.ic1eq + c.a3 * v3;
+        self.ic1eq = Simd::splat(2.) * v1 - self.ic1eq;
+        self.ic2eq = Simd::splat(2.) * v2 - self.ic2eq;
+        (v2, x - Simd::splat(K) * v1 - v2)
+    }
+}
+
+/// Fourth order Linkwitz-Riley crossover, splitting a signal into low and high bands,
+/// each through two Butterworth filters in series. The bands are in phase at every
+/// frequency, and sum up to an allpass of the input, so, with a flat magnitude
+#[derive(Default)]
+pub struct Crossover {
+    low: [Svf; 2],
+    high: [Svf; 2],
+    coefficients: Option<SvfCoefficients>,
+}
+
+impl Crossover {
+    pub fn reset(&mut self) {
+        let coefficients = self.coefficients;
+        *self = Self {
+            coefficients,
+            ..Self::default()
+        };
+    }
+
+    /// Moves the crossover to `freq_ratio` times the sample rate
+    pub fn set(&mut self, freq_ratio: f32) {
+        self.coefficients = Some(SvfCoefficients::new(freq_ratio));
+    }
+
+    /// The low and high bands of `x`, silent before the first [`Self::set`]
+    pub fn split(&mut self, x: f32x2) -> (f32x2, f32x2) {
+        let Some(c) = &self.coefficients else {
+            return (Simd::splat(0.), Simd::splat(0.));
+        };
+
+        let [low_1, low_2] = &mut self.low;
+        let [high_1, high_2] = &mut self.high;
+        let (low, _) = low_1.process(c, x);
+        let (low, _) = low_2.process(c, low);
+        let (_, high) = high_1.process(c, x);
+        let (_, high) = high_2.process(c, high);
+        (low, high)
+    }
+}
@@ -0,0 +1,139 @@
+use nih_plug::prelude::*;
+
+/// How the filters' state is kept out of the denormal range, where floating
+/// point math slows down to a crawl, as it decays during long silences
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DenormalMode {
+    /// No protection at all
+    Off,
+    /// The CPU's flush-to-zero (and denormals-are-zero) modes are turned on around
+    /// processing (see [`ScopedFtz`]), costing nothing per sample. Only x86 (with SSE)
+    /// and AArch64 CPUs have those, elsewhere, this is the same as `Off`
+    #[default]
+    #[name = "FTZ flag"]
+    Ftz,
+    /// Noise, far below the quietest signal any converter can output,
+    /// is added to the input, working on any CPU
+    Noise,
+}
+
+/// Bits of MXCSR enabling flush-to-zero (15) and denormals-are-zero (6)
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+const FTZ_BITS: usize = (1 << 15) | (1 << 6);
+
+/// Bit of FPCR enabling flush-to-zero, for both inputs and outputs
+#[cfg(target_arch = "aarch64")]
+const FTZ_BITS: usize = 1 << 24;
+
+/// Turns the current thread's flush-to-zero mode on, until it's dropped, when the
+/// previous mode is restored. Some hosts, and nih-plug's wrappers, already do so
+/// around `process`, this takes care of the others, and of `process_block`
+pub struct ScopedFtz {
+    /// control register value, before enabling flush-to-zero
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+    previous: usize,
+}
+
+impl ScopedFtz {
+    pub fn enable() -> Self {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+        {
+            let previous = read_control();
+            // SAFETY: these modes only change how tiny floats are rounded
+            unsafe { write_control(previous | FTZ_BITS) };
+            Self { previous }
+        }
+
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+        Self {}
+    }
+}
+
+impl Drop for ScopedFtz {
+    fn drop(&mut self) {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+        // SAFETY: the register is restored to the value it had before
+        unsafe {
+            write_control(self.previous)
+        };
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn read_control() -> usize {
+    let mut mxcsr = 0u32;
+    // SAFETY: only stores MXCSR in `mxcsr`
+    unsafe {
+        core::arch::asm!(
+            "stmxcsr [{}]",
+            in(reg) &mut mxcsr,
+            options(nostack, preserves_flags),
+        );
+    }
+    mxcsr as usize
+}
+
+/// # Safety
+///
+/// `value` must be a valid MXCSR value
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+unsafe fn write_control(value: usize) {
+    let mxcsr = value as u32;
+    unsafe {
+        core::arch::asm!(
+            "ldmxcsr [{}]",
+            in(reg) &mxcsr,
+            options(nostack, readonly, preserves_flags),
+        );
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+fn read_control() -> usize {
+    let fpcr: usize;
+    // SAFETY: only reads FPCR
+    unsafe {
+        core::arch::asm!("mrs {}, fpcr", out(reg) fpcr, options(nomem, nostack, preserves_flags));
+    }
+    fpcr
+}
+
+/// # Safety
+///
+/// `value` must be a valid FPCR value
+#[cfg(target_arch = "aarch64")]
+unsafe fn write_control(value: usize) {
+    unsafe {
+        core::arch::asm!("msr fpcr, {}", in(reg) value, options(nomem, nostack, preserves_flags));
+    }
+}
+
+/// Cheap white noise generator, for [`DenormalMode::Noise`]
+#[derive(Default)]
+pub struct Noise {
+    /// where `state` starts from
+    seed: u32,
+    /// linear congruential generator state, any value works
+    state: u32,
+}
+
+impl Noise {
+    /// A generator starting from `seed`, generators with different seeds
+    /// give noise unrelated to each other's
+    pub fn new(seed: u32) -> Self {
+        // spread apart, consecutive seeds would start out almost in step
+        let seed = seed.wrapping_mul(0x9E37_79B9);
+        Self { seed, state: seed }
+    }
+
+    /// Restarts the noise from where it started, from its seed
+    pub fn reset(&mut self) {
+        self.state = self.seed;
+    }
+
+    /// Next sample of noise, peaking at `level`
+    pub fn next(&mut self, level: f32) -> f32 {
+        self.state = self.state.wrapping_mul(1664525).wrapping_add(1013904223);
+        self.state as i32 as f32 * (level / 2147483648.)
+    }
+}
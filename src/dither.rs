@@ -0,0 +1,82 @@
+use nih_plug::prelude::*;
+use plugin_util::simd::*;
+
+use crate::denormal::Noise;
+
+/// Bit depth the output is dithered for, that of CDs, the usual reason to dither
+const BITS: u32 = 16;
+/// Step between two consecutive levels at `BITS` bits, full scale being `±1`
+const LSB: f32 = 1. / (1 << (BITS - 1)) as f32;
+
+/// How the main output is prepared for its conversion down to 16 bits, when bouncing
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dither {
+    /// The output is left untouched
+    #[default]
+    Off,
+    /// Triangular noise, peaking at 1 LSB, is added, so that the host's rounding error
+    /// doesn't follow the signal: no distortion on fades and quiet passages, only a
+    /// very low, steady hiss
+    #[name = "TPDF"]
+    Tpdf,
+    /// The output is rounded to 16 bits here, after TPDF dither, with the rounding
+    /// error fed back, pushing the hiss up towards Nyquist, where it's hardly heard.
+    /// Only exact when the host converts to 16 bits without changing the gain
+    #[name = "Noise-shaped"]
+    Shaped,
+}
+
+/// Dithers a pair of channels, each with noise of its own
+#[derive(Default)]
+pub struct Ditherer {
+    /// of each lane
+    noise: [Noise; 2],
+    /// previous rounding error, with `Dither::Shaped`
+    error: f32x2,
+}
+
+impl Ditherer {
+    /// Dither for the `pair`-th pair of channels, its noise seeded apart from
+    /// every other channel's
+    pub fn new(pair: usize) -> Self {
+        let seed = 2 * pair as u32;
+        Self {
+            noise: [Noise::new(seed), Noise::new(seed + 1)],
+            ..Default::default()
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.error = Simd::splat(0.);
+    }
+
+    /// Restarts the noise from its seeds, see [`Noise::reset`]
+    pub fn reseed(&mut self) {
+        for noise in &mut self.noise {
+            noise.reset();
+        }
+    }
+
+    /// Triangular noise, peaking at 1 LSB, the sum of two uniform ones
+    fn tpdf(&mut self) -> f32x2 {
+        Simd::from_array(
+            self.noise
+                .each_mut()
+                .map(|noise| noise.next(LSB / 2.) + noise.next(LSB / 2.)),
+        )
+    }
+
+    pub fn process(&mut self, mode: Dither, x: f32x2) -> f32x2 {
+        match mode {
+            Dither::Off => x,
+            Dither::Tpdf => x + self.tpdf(),
+            Dither::Shaped => {
+                let x = x - self.error;
+                let dithered = ((x + self.tpdf()) / Simd::splat(LSB)).to_array();
+                let rounded = Simd::from_array(dithered.map(f32::round)) * Simd::splat(LSB);
+                self.error = rounded - x;
+                rounded
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use plugin_util::{filter::one_pole::FilterMode, simd::*};
+
+use crate::{
+    stage::{Stage, Stages},
+    MAX_STAGES,
+};
+
+/// Coefficient, gliding linearly towards its target
+#[derive(Default, Clone, Copy)]
+struct Smoothed {
+    value: f64x2,
+    step: f64x2,
+}
+
+/// The bilinear transform of the analog one-pole filter, like `OnePole`, in its
+/// topology-preserving form, but with its state and coefficients in double precision.
+/// Only the input and outputs are single precision.
+///
+/// With very low cutoffs, the state moves by tiny amounts every sample, a single
+/// precision state rounds a fair share of them away, and, over several stages in
+/// series, these errors add up to an audible noise floor, this keeps it far below that.
+/// The outputs other than the lowpass are derived the same way as [`MatchedOnePole`]'s
+///
+/// [`MatchedOnePole`]: crate::matched::MatchedOnePole
+#[derive(Default)]
+pub struct DoubleOnePole {
+    /// `g / (1 + g)`, `g` being the prewarped cutoff, `tan(w_c / 2)`
+    k: Smoothed,
+    /// shelves' gain
+    gain: Smoothed,
+    /// number of samples left before the coefficients reach their targets
+    remaining: usize,
+    /// integrator state
+    s: f64x2,
+    /// latest input
+    x: f64x2,
+    /// current lowpass output
+    y: f64x2,
+}
+
+impl DoubleOnePole {
+    fn coefficient(w_c: f32x2) -> f64x2 {
+        let g = Simd::from_array(w_c.to_array().map(|w| (f64::from(w) * 0.5).tan()));
+        g / (Simd::splat(1.) + g)
+    }
+
+    fn update(&mut self, w_c: f32x2, gain: f32x2) {
+        for (c, target) in [&mut self.k, &mut self.gain]
+            .into_iter()
+            .zip([Self::coefficient(w_c), gain.cast()])
+        {
+            *c = Smoothed {
+                value: target,
+                step: Simd::splat(0.),
+            };
+        }
+        self.remaining = 0;
+    }
+
+    fn update_smoothed(&mut self, w_c: f32x2, gain: f32x2, num_samples: usize) {
+        let n = Simd::splat(num_samples.max(1) as f64);
+        for (c, target) in [&mut self.k, &mut self.gain]
+            .into_iter()
+            .zip([Self::coefficient(w_c), gain.cast()])
+        {
+            c.step = (target - c.value) / n;
+        }
+        self.remaining = num_samples;
+    }
+
+    fn lowpass(&self) -> f32x2 {
+        self.y.cast()
+    }
+
+    fn highpass(&self) -> f32x2 {
+        (self.x - self.y).cast()
+    }
+
+    fn allpass(&self) -> f32x2 {
+        (self.y - (self.x - self.y)).cast()
+    }
+
+    fn low_shelf(&self) -> f32x2 {
+        (self.gain.value * self.y + (self.x - self.y)).cast()
+    }
+
+    fn high_shelf(&self) -> f32x2 {
+        (self.y + self.gain.value * (self.x - self.y)).cast()
+    }
+}
+
+impl Stage for DoubleOnePole {
+    fn of(stages: &mut Stages) -> &mut [Self; MAX_STAGES] {
+        &mut stages.double
+    }
+
+    fn update_function(_mode: FilterMode) -> fn(&mut Self, f32x2, f32x2) {
+        Self::update
+    }
+
+    fn smoothing_update_function(_mode: FilterMode) -> fn(&mut Self, f32x2, f32x2, usize) {
+        Self::update_smoothed
+    }
+
+    fn output_function(mode: FilterMode) -> fn(&Self) -> f32x2 {
+        match mode {
+            FilterMode::LP => Self::lowpass,
+            FilterMode::HP => Self::highpass,
+            FilterMode::AP => Self::allpass,
+            FilterMode::LSH => Self::low_shelf,
+            FilterMode::HSH => Self::high_shelf,
+        }
+    }
+
+    fn update_smoothers(&mut self) {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            for c in [&mut self.k, &mut self.gain] {
+                c.value += c.step;
+            }
+        }
+    }
+
+    fn process(&mut self, x: f32x2) {
+        let x = x.cast();
+        let v = self.k.value * (x - self.s);
+        self.y = v + self.s;
+        self.s = self.y + v;
+        self.x = x;
+    }
+
+    fn reset(&mut self) {
+        self.s = Simd::splat(0.);
+        self.x = Simd::splat(0.);
+        self.y = Simd::splat(0.);
+    }
+}
@@ -0,0 +1,77 @@
+/// Time between two steps of the walk
+const STEP_MS: f32 = 20.;
+
+/// Fraction of the offset kept at each step, pulling the walk back towards the
+/// knob's value, with a time constant of about 4 seconds
+const LEAK: f32 = 0.995;
+
+/// Largest change of the offset in a single step. With `LEAK`, the offset
+/// wanders around 0 with a standard deviation of about a third
+const STEP_SIZE: f32 = 0.06;
+
+/// The walk restarts from there on every reset, so that renders are reproducible
+const SEED: u32 = 0x9E37_79B9;
+
+/// Slow random walk of the cutoff, emulating component drift, by up to
+/// the `drift` parameter's amount, in cents, either way.
+///
+/// It steps every `STEP_MS`, however the host splits its blocks, so, from a
+/// reset, the same audio is always rendered with the same drift
+pub struct Drift {
+    /// xorshift state, never 0
+    state: u32,
+    /// in `-1..=1`
+    offset: f32,
+    /// samples left before the next step
+    countdown: u32,
+    /// samples between two steps
+    interval: u32,
+}
+
+impl Default for Drift {
+    fn default() -> Self {
+        Self {
+            state: SEED,
+            offset: 0.,
+            countdown: 0,
+            interval: 1,
+        }
+    }
+}
+
+impl Drift {
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.interval = ((STEP_MS / 1000. * sample_rate) as u32).max(1);
+        self.countdown = self.countdown.min(self.interval);
+    }
+
+    pub fn reset(&mut self) {
+        self.state = SEED;
+        self.offset = 0.;
+        self.countdown = self.interval;
+    }
+
+    /// Uniformly distributed in `-1..=1`
+    fn next_noise(&mut self) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        self.state as f32 / u32::MAX as f32 * 2. - 1.
+    }
+
+    /// Moves the walk forward by `num_samples`
+    pub fn advance(&mut self, mut num_samples: u32) {
+        while num_samples >= self.countdown {
+            num_samples -= self.countdown;
+            self.countdown = self.interval;
+            let noise = self.next_noise();
+            self.offset = (self.offset * LEAK + noise * STEP_SIZE).clamp(-1., 1.);
+        }
+        self.countdown -= num_samples;
+    }
+
+    /// Offset to apply to the cutoff frequency, in cents, for a drift of up to `amount` cents
+    pub fn cents(&self, amount: f32) -> f32 {
+        self.offset * amount
+    }
+}
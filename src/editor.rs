@@ -0,0 +1,398 @@
+use nih_plug::prelude::*;
+use nih_plug_egui::{create_egui_editor, egui, widgets::ParamSlider, EguiState};
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    bank::{self, PresetTask},
+    learn::{MidiMap, Target},
+    meters::Meters,
+    presets::{Preset, PRESETS},
+    OnePoleFilter, OnePoleParams,
+};
+
+/// Frequencies the response curve is computed at
+const CURVE_POINTS: usize = 128;
+const CURVE_HEIGHT: f32 = 100.;
+/// Range of the response curve's plot, those outside of it are clamped
+const CURVE_MIN_DB: f32 = -36.;
+const CURVE_MAX_DB: f32 = 24.;
+
+/// How long the ceiling's indicator stays lit after it was last hit, unless clicked
+const CEILING_HOLD: Duration = Duration::from_secs(1);
+
+/// What the editor keeps from one frame to the next
+struct EditorState {
+    /// see [`follow_linked_cutoffs`]
+    last_cutoffs: [f32; 2],
+    /// when the ceiling was last hit, `None` once its indicator was cleared
+    ceiling_hit: Option<Instant>,
+}
+
+pub(crate) fn default_state() -> Arc<EguiState> {
+    EguiState::from_size(300, 700)
+}
+
+/// Sets `param` to `value`, as a single, complete gesture
+pub(crate) fn set_parameter<P: Param>(setter: &ParamSetter, param: &P, value: P::Plain) {
+    setter.begin_set_parameter(param);
+    setter.set_parameter(param, value);
+    setter.end_set_parameter(param);
+}
+
+/// With stereo link on, moving either cutoff knob moves the other one along.
+/// `last` holds both cutoffs' values as of the previous frame
+fn follow_linked_cutoffs(params: &OnePoleParams, setter: &ParamSetter, last: &mut [f32; 2]) {
+    let l = params.cutoff_l.unmodulated_plain_value();
+    let r = params.cutoff_r.unmodulated_plain_value();
+    let (moved_l, moved_r) = (l != last[0], r != last[1]);
+
+    if params.link.value() && l != r {
+        if moved_l {
+            set_parameter(setter, &params.cutoff_r, l);
+        } else if moved_r {
+            set_parameter(setter, &params.cutoff_l, r);
+        }
+    }
+
+    *last = [l, r];
+}
+
+/// What the CCs are bound to, or which parameter the next one will be bound to
+fn midi_map_status(midi_map: &MidiMap) -> String {
+    let target_name = |target| match target {
+        Target::Cutoff => "Cutoff",
+        Target::Gain => "Gain",
+    };
+
+    if let Some(target) = midi_map.learning() {
+        return format!(
+            "Move a controller for the {}",
+            target_name(target).to_lowercase()
+        );
+    }
+
+    [Target::Cutoff, Target::Gain]
+        .map(|target| match midi_map.cc(target) {
+            Some(cc) => format!("{}: CC {cc}", target_name(target)),
+            None => format!("{}: none", target_name(target)),
+        })
+        .join(", ")
+}
+
+/// How much louder, or quieter, the output's peaks are than the input's, in dB
+fn level_change(input_db: [f32; 2], output_db: [f32; 2]) -> String {
+    let [l, r] = [0, 1].map(|lane| {
+        // silence reads as -100 dB, there's no meaningful change to show then
+        if input_db[lane] <= util::MINUS_INFINITY_DB {
+            "-".to_owned()
+        } else {
+            format!("{:+.1} dB", output_db[lane] - input_db[lane])
+        }
+    });
+    format!("Level Change: L {l}, R {r}")
+}
+
+/// Draws the plugin's magnitude response, from the cutoff range's ends,
+/// with a line at 0 dB. Nothing until the audio thread is running
+fn draw_response(ui: &mut egui::Ui, params: &OnePoleParams, meters: &Meters) {
+    let Some(operating) = meters.response.get() else {
+        return;
+    };
+    let curve = params.response_curve(operating, CURVE_POINTS, false);
+
+    let size = egui::vec2(ui.available_width(), CURVE_HEIGHT);
+    let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+    let rect = response.rect;
+    let y = |db: f32| {
+        let db = db.clamp(CURVE_MIN_DB, CURVE_MAX_DB);
+        rect.top() + (CURVE_MAX_DB - db) / (CURVE_MAX_DB - CURVE_MIN_DB) * rect.height()
+    };
+
+    painter.rect_filled(rect, 0., egui::Color32::DARK_GRAY);
+    painter.line_segment(
+        [
+            egui::pos2(rect.left(), y(0.)),
+            egui::pos2(rect.right(), y(0.)),
+        ],
+        (1., egui::Color32::GRAY),
+    );
+
+    // the frequencies are evenly spaced in pitch already
+    let last = curve.magnitudes.len().max(2) - 1;
+    let points = curve
+        .magnitudes
+        .iter()
+        .enumerate()
+        .map(|(i, &gain)| {
+            let x = rect.left() + i as f32 / last as f32 * rect.width();
+            egui::pos2(x, y(util::gain_to_db(gain)))
+        })
+        .collect();
+    painter.add(egui::Shape::line(points, (1.5, egui::Color32::LIGHT_BLUE)));
+}
+
+pub(crate) fn create(
+    params: Arc<OnePoleParams>,
+    meters: Arc<Meters>,
+    async_executor: AsyncExecutor<OnePoleFilter>,
+) -> Option<Box<dyn Editor>> {
+    let state = EditorState {
+        last_cutoffs: [
+            params.cutoff_l.unmodulated_plain_value(),
+            params.cutoff_r.unmodulated_plain_value(),
+        ],
+        ceiling_hit: None,
+    };
+
+    create_egui_editor(
+        params.editor_state.clone(),
+        state,
+        |_, _| {},
+        move |egui_ctx, setter, state| {
+            egui::CentralPanel::default().show(egui_ctx, |ui| {
+                // taller than most screens, laid out in one column
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    ui.heading("One Pole Filter");
+
+                    ui.horizontal(|ui| {
+                        for preset in PRESETS {
+                            if ui.button(preset.name.as_ref()).clicked() {
+                                preset.apply(&params, setter);
+                            }
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        // released before saving, tasks may run right away, on this thread
+                        let num_user_presets = {
+                            let user_presets = bank::user_presets();
+                            for preset in user_presets.iter() {
+                                if ui.button(preset.name.as_ref()).clicked() {
+                                    preset.apply(&params, setter);
+                                }
+                            }
+                            user_presets.len()
+                        };
+
+                        if ui.button("Save").clicked() {
+                            let name = format!("User {}", num_user_presets + 1);
+                            let preset = Preset::capture(&params, name);
+                            async_executor.execute_background(PresetTask::Save(preset));
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        let active = params.compare.active();
+                        for (slot, name) in ["A", "B"].into_iter().enumerate() {
+                            if ui.selectable_label(slot == active, name).clicked() {
+                                params.compare.switch(params.as_ref(), setter, slot);
+                            }
+                        }
+                        let other = if active == 0 { "B" } else { "A" };
+                        if ui.button(format!("Copy to {other}")).clicked() {
+                            params.compare.copy_to_other(params.as_ref());
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Learn").clicked() {
+                            params.midi_map.arm();
+                        }
+                        ui.label(midi_map_status(&params.midi_map));
+                    });
+
+                    draw_response(ui, &params, &meters);
+
+                    ui.label("Range Min");
+                    ui.add(ParamSlider::for_param(&params.range_min, setter));
+
+                    ui.label("Range Max");
+                    ui.add(ParamSlider::for_param(&params.range_max, setter));
+                    ui.add(ParamSlider::for_param(&params.skew, setter));
+
+                    ui.label("Left Cutoff");
+                    ui.add(ParamSlider::for_param(&params.cutoff_l, setter));
+
+                    ui.label("Right Cutoff");
+                    ui.add(ParamSlider::for_param(&params.cutoff_r, setter));
+
+                    ui.label("Stereo Link");
+                    ui.add(ParamSlider::for_param(&params.link, setter));
+
+                    ui.label("Mid/Side");
+                    ui.add(ParamSlider::for_param(&params.ms_mode, setter));
+
+                    ui.label("DC Block");
+                    ui.add(ParamSlider::for_param(&params.dc_block, setter));
+                    ui.add(ParamSlider::for_param(&params.rumble, setter));
+
+                    ui.label("Width");
+                    ui.add(ParamSlider::for_param(&params.width, setter));
+                    ui.add(ParamSlider::for_param(&params.haas, setter));
+
+                    ui.label("Trim");
+                    ui.add(ParamSlider::for_param(&params.trim_l, setter));
+                    ui.add(ParamSlider::for_param(&params.trim_r, setter));
+
+                    follow_linked_cutoffs(&params, setter, &mut state.last_cutoffs);
+
+                    ui.label("Gain");
+                    ui.add(ParamSlider::for_param(&params.gain, setter));
+                    ui.add(ParamSlider::for_param(&params.gain_position, setter))
+                        .on_hover_text(
+                            "Filter: the shelves' own gain, ignored in the other modes\n\
+                            Pre: input level, driving the saturation and resonance harder\n\
+                            Post: output level, after all filtering\n\
+                            Auto (default): the shelves' gain in the shelf modes, \
+                            the output level in the others",
+                        );
+
+                    ui.add(ParamSlider::for_param(&params.gain_mode, setter));
+
+                    ui.label("Auto Gain");
+                    ui.add(ParamSlider::for_param(&params.auto_gain, setter));
+
+                    ui.label("Resonance");
+                    ui.add(ParamSlider::for_param(&params.resonance, setter));
+                    ui.add(ParamSlider::for_param(&params.resonance_headroom, setter));
+                    ui.add(ParamSlider::for_param(&params.self_osc, setter));
+
+                    ui.label("Drive");
+                    ui.add(ParamSlider::for_param(&params.drive, setter));
+                    ui.add(ParamSlider::for_param(&params.warmth, setter));
+                    ui.add(ParamSlider::for_param(&params.vintage, setter));
+                    ui.add(ParamSlider::for_param(&params.drift, setter));
+
+                    ui.label("Mix");
+                    ui.add(ParamSlider::for_param(&params.mix, setter));
+                    ui.add(ParamSlider::for_param(&params.mix_law, setter));
+
+                    ui.label("Morph");
+                    ui.add(ParamSlider::for_param(&params.morph, setter));
+                    ui.horizontal(|ui| {
+                        // the cutoff, gain, mix and mode, as they're set right now
+                        for (i, name) in ["A", "B"].into_iter().enumerate() {
+                            let stored = params.morph_points.point(i).is_some();
+                            let label = format!("Store {name}{}", if stored { " ✓" } else { "" });
+                            if ui.button(label).clicked() {
+                                params.morph_points.store(i, params.morph_point());
+                            }
+                        }
+                    });
+
+                    ui.label("Freeze");
+                    ui.add(ParamSlider::for_param(&params.freeze, setter));
+
+                    ui.label("Invert");
+                    ui.add(ParamSlider::for_param(&params.invert, setter));
+
+                    ui.label("Routing");
+                    ui.add(ParamSlider::for_param(&params.routing, setter));
+
+                    ui.label("Crossover");
+                    ui.add(ParamSlider::for_param(&params.crossover, setter));
+                    ui.add(ParamSlider::for_param(&params.crossover_freq, setter));
+
+                    ui.label("Filter Mode");
+                    ui.add(ParamSlider::for_param(&params.mode, setter));
+
+                    ui.label("Damping");
+                    ui.add(ParamSlider::for_param(&params.damping, setter));
+
+                    ui.label("Tilt");
+                    ui.add(ParamSlider::for_param(&params.tilt, setter));
+
+                    ui.label("Tilt EQ Slope");
+                    ui.add(ParamSlider::for_param(&params.slope_db, setter));
+
+                    ui.label("Pivot");
+                    ui.add(ParamSlider::for_param(&params.pivot, setter));
+                    ui.add(ParamSlider::for_param(&params.pivot_follow, setter));
+
+                    ui.label("Key Tracking");
+                    ui.add(ParamSlider::for_param(&params.key_track, setter));
+                    ui.add(ParamSlider::for_param(&params.glide, setter));
+
+                    ui.label("Expression Depth");
+                    ui.add(ParamSlider::for_param(&params.expression_depth, setter));
+
+                    ui.label("LFO");
+                    ui.add(ParamSlider::for_param(&params.lfo_rate, setter));
+                    ui.add(ParamSlider::for_param(&params.lfo_depth, setter));
+                    ui.add(ParamSlider::for_param(&params.lfo_shape, setter));
+
+                    ui.label("Envelope");
+                    ui.add(ParamSlider::for_param(&params.env_depth, setter));
+                    ui.add(ParamSlider::for_param(&params.env_attack, setter));
+                    ui.add(ParamSlider::for_param(&params.env_release, setter));
+
+                    ui.label("FM");
+                    ui.add(ParamSlider::for_param(&params.fm_ratio, setter));
+                    ui.add(ParamSlider::for_param(&params.fm_depth, setter));
+
+                    ui.label("Smoothing");
+                    ui.add(ParamSlider::for_param(&params.smoothing_ms, setter));
+
+                    ui.label("Slope");
+                    ui.add(ParamSlider::for_param(&params.slope, setter));
+                    for stage in &params.stages {
+                        ui.add(ParamSlider::for_param(&stage.enabled, setter));
+                    }
+
+                    ui.label("Character");
+                    ui.add(ParamSlider::for_param(&params.character, setter));
+
+                    ui.label("Oversampling");
+                    ui.add(ParamSlider::for_param(&params.oversampling, setter));
+                    ui.add(ParamSlider::for_param(&params.quality, setter));
+                    ui.add(ParamSlider::for_param(&params.chunk_size, setter));
+
+                    ui.label("Denormals");
+                    ui.add(ParamSlider::for_param(&params.denormal_mode, setter));
+
+                    ui.label("Monitor");
+                    ui.add(ParamSlider::for_param(&params.monitor, setter));
+
+                    let input = meters.input.get().map(util::gain_to_db);
+                    let output = meters.output.get().map(util::gain_to_db);
+                    let [l, r] = input;
+                    ui.label(format!("Input Peak: L {l:.1} dBFS, R {r:.1} dBFS"));
+                    let [l, r] = output;
+                    ui.label(format!("Output Peak: L {l:.1} dBFS, R {r:.1} dBFS"));
+                    let [l, r] = meters.output_rms().map(util::gain_to_db);
+                    ui.label(format!("Output RMS: L {l:.1} dBFS, R {r:.1} dBFS"));
+                    ui.label(level_change(input, output));
+
+                    ui.horizontal(|ui| {
+                        ui.label("Ceiling");
+                        // a block's hit would only light it for a frame
+                        if meters.take_ceiling_hit() {
+                            state.ceiling_hit = Some(Instant::now());
+                        }
+                        let held = state
+                            .ceiling_hit
+                            .is_some_and(|hit| hit.elapsed() < CEILING_HOLD);
+                        if held
+                            && ui
+                                .colored_label(egui::Color32::RED, "clipping")
+                                .interact(egui::Sense::click())
+                                .on_hover_text("Click to clear")
+                                .clicked()
+                        {
+                            state.ceiling_hit = None;
+                        }
+                    });
+                    ui.add(ParamSlider::for_param(&params.ceiling_mode, setter));
+                    ui.add(ParamSlider::for_param(&params.ceiling, setter));
+
+                    ui.label("Dither");
+                    ui.add(ParamSlider::for_param(&params.dither, setter));
+                });
+            });
+        },
+    )
+}
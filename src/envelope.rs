@@ -0,0 +1,52 @@
+use crate::modulation::CENTS_PER_OCTAVE;
+
+/// Level below which the envelope is considered silent, -120 dB. Snapping it to 0
+/// there keeps it from decaying, forever, into the denormal range
+const FLOOR: f32 = 1e-6;
+
+/// Peak envelope follower, moving the cutoff by up to `depth` octaves, either way,
+/// as the level goes from silence to full scale
+#[derive(Default)]
+pub struct Envelope {
+    /// linear
+    value: f32,
+    /// per sample weight of the previous value, while the level rises
+    attack: f32,
+    /// same, while it falls
+    release: f32,
+    depth: f32,
+}
+
+/// Weight of the previous value for a one-pole smoother to reach about
+/// 63% of the way to its target in `ms` milliseconds
+fn coefficient(ms: f32, sample_rate: f32) -> f32 {
+    (-1000. / (ms * sample_rate)).exp()
+}
+
+impl Envelope {
+    pub fn set(&mut self, attack_ms: f32, release_ms: f32, depth: f32, sample_rate: f32) {
+        self.attack = coefficient(attack_ms, sample_rate);
+        self.release = coefficient(release_ms, sample_rate);
+        self.depth = depth;
+    }
+
+    pub fn reset(&mut self) {
+        self.value = 0.;
+    }
+
+    /// Follows `level`, the peak absolute value of the next input sample, and
+    /// returns the offset to apply to the cutoff frequency for that sample, in cents
+    pub fn next_cents(&mut self, level: f32) -> f32 {
+        let coefficient = if level > self.value {
+            self.attack
+        } else {
+            self.release
+        };
+        self.value = level + coefficient * (self.value - level);
+        if self.value < FLOOR {
+            self.value = 0.;
+        }
+
+        self.depth * self.value.min(1.) * CENTS_PER_OCTAVE
+    }
+}
@@ -0,0 +1,33 @@
+use core::f32::consts::TAU;
+
+use crate::modulation::CENTS_PER_OCTAVE;
+
+/// Frequency of MIDI note 69, A4, in Hz
+const A4_HZ: f32 = 440.;
+
+/// Sine oscillator modulating the cutoff at audio rate, by up to `depth` octaves,
+/// either way. Its frequency is a multiple of that of the note followed by key
+/// tracking, so that the sidebands it adds stay in tune with what's played
+#[derive(Default)]
+pub struct Fm {
+    /// in cycles
+    phase: f32,
+}
+
+impl Fm {
+    pub fn reset(&mut self) {
+        self.phase = 0.;
+    }
+
+    /// Advances the oscillator by a sample, with a frequency of `ratio` times
+    /// that of (fractional) MIDI note `note`, and returns the offset to apply to
+    /// the cutoff frequency for that sample, in cents
+    pub fn next_cents(&mut self, note: f32, ratio: f32, depth: f32, sample_rate: f32) -> f32 {
+        let value = (TAU * self.phase).sin();
+
+        let hz = A4_HZ * ((note - 69.) / 12.).exp2() * ratio;
+        self.phase = (self.phase + hz / sample_rate).fract();
+
+        value * depth * CENTS_PER_OCTAVE
+    }
+}
@@ -0,0 +1,85 @@
+use nih_plug::prelude::*;
+use plugin_util::simd::*;
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::mode::Mode;
+
+/// Where, in the signal chain, the `gain` parameter is applied
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GainPosition {
+    /// The filters' own gain, only the shelves have one, in the other modes,
+    /// the gain does nothing
+    Filter,
+    /// Before the filters, so, also before the drive and the resonance's feedback loop,
+    /// pushing them harder. The filters themselves have unity gain
+    Pre,
+    /// After the filters' output, as a plain output level. The filters themselves
+    /// have unity gain
+    Post,
+    /// The default, `Filter` in the shelf modes, where it shapes the response,
+    /// `Post` in the others, instead of doing nothing there
+    #[default]
+    Auto,
+}
+
+impl GainPosition {
+    /// Where the gain goes in `mode`, `Auto` is resolved, the others are left as is
+    fn for_mode(self, mode: Mode) -> Self {
+        match self {
+            Self::Auto if mode.has_gain() => Self::Filter,
+            Self::Auto => Self::Post,
+            position => position,
+        }
+    }
+
+    /// Splits `gain` into the filters' own gain, and the ones applied before
+    /// and after them, in `mode`. Two of these are always 1
+    pub fn split(self, mode: Mode, gain: f32x2) -> (f32x2, f32x2, f32x2) {
+        let unity = Simd::splat(1.);
+        match self.for_mode(mode) {
+            Self::Filter => (gain, unity, unity),
+            Self::Pre => (unity, gain, unity),
+            Self::Post => (unity, unity, gain),
+            Self::Auto => unreachable!("resolved by `for_mode`"),
+        }
+    }
+}
+
+/// Which side of 0 dB the `gain` parameter may go, for safer moves, the rest
+/// of its range leaves the signal alone
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GainLimit {
+    #[default]
+    Full,
+    #[name = "Cut only"]
+    CutOnly,
+    #[name = "Boost only"]
+    BoostOnly,
+}
+
+impl GainLimit {
+    /// The gain actually applied, in dB, for a knob value of `db`
+    pub fn clamp(self, db: f32) -> f32 {
+        match self {
+            Self::Full => db,
+            Self::CutOnly => db.min(0.),
+            Self::BoostOnly => db.max(0.),
+        }
+    }
+}
+
+/// The current [`GainLimit`], shared between the `gain_mode` parameter's callback
+/// and the `gain` parameter's display closure
+#[derive(Default)]
+pub struct SharedGainLimit(AtomicUsize);
+
+impl SharedGainLimit {
+    pub fn set(&self, limit: GainLimit) {
+        self.0.store(limit.to_index(), Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> GainLimit {
+        GainLimit::from_index(self.0.load(Ordering::Relaxed))
+    }
+}
@@ -0,0 +1,49 @@
+/// Distance to the target, in semitones, below which a glide is considered over
+const SNAP: f32 = 1e-3;
+
+/// Portamento of the note followed by key tracking, gliding exponentially from one
+/// note to the next. Notes are kept fractional, so a glide moves evenly in pitch
+#[derive(Default)]
+pub struct Glide {
+    /// `None` until the first note, which is jumped to
+    note: Option<f32>,
+    /// per sample weight of the previous note
+    coefficient: f32,
+}
+
+impl Glide {
+    /// Makes glides reach about 63% of the way to the next note in `ms` milliseconds,
+    /// or jump straight to it if `ms` is 0
+    pub fn set_time(&mut self, ms: f32, sample_rate: f32) {
+        self.coefficient = if ms > 0. {
+            (-1000. / (ms * sample_rate)).exp()
+        } else {
+            0.
+        };
+    }
+
+    pub fn note(&self) -> Option<f32> {
+        self.note
+    }
+
+    /// Whether the note is still on its way to `target`
+    pub fn is_gliding(&self, target: Option<u8>) -> bool {
+        target.is_some_and(|target| self.note != Some(f32::from(target)))
+    }
+
+    /// Moves the note one sample closer to `target`, the most recently played note,
+    /// and returns it. A new target, mid-glide, is glided to from where the note is
+    pub fn next(&mut self, target: Option<u8>) -> Option<f32> {
+        let target = f32::from(target?);
+        let note = self.note.map_or(target, |note| {
+            let note = target + self.coefficient * (note - target);
+            if (note - target).abs() < SNAP {
+                target
+            } else {
+                note
+            }
+        });
+        self.note = Some(note);
+        self.note
+    }
+}
@@ -0,0 +1,51 @@
+use plugin_util::simd::*;
+
+/// Longest delay `haas` can be set to
+pub const MAX_DELAY_MS: f32 = 30.;
+
+/// Delays the right lane by a few milliseconds, for the Haas effect: panned towards
+/// the left, but sounding wider. The delay is fractional, read between samples, so
+/// that its smoother glides from one to the next, without clicking
+#[derive(Default)]
+pub struct Haas {
+    /// the right lane's past samples, empty until `allocate` is called
+    buffer: Vec<f32>,
+    /// where the next sample goes
+    pos: usize,
+}
+
+impl Haas {
+    /// Makes room for `MAX_DELAY_MS` at `sample_rate`, this allocates, so the
+    /// audio thread can't call it, with higher rates, delays are cut short
+    pub fn allocate(&mut self, sample_rate: f32) {
+        // the interpolation reads one sample past the delay
+        let len = (MAX_DELAY_MS / 1000. * sample_rate).ceil() as usize + 2;
+        if self.buffer.len() != len {
+            self.buffer = vec![0.; len];
+            self.pos = 0;
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.buffer.fill(0.);
+    }
+
+    /// `x`, its right lane delayed by `delay` samples, with linear interpolation
+    pub fn process(&mut self, x: f32x2, delay: f32) -> f32x2 {
+        let len = self.buffer.len();
+        if len == 0 {
+            return x;
+        }
+
+        let [l, r] = x.to_array();
+        self.buffer[self.pos] = r;
+
+        let delay = delay.clamp(0., (len - 2) as f32);
+        let (whole, frac) = (delay as usize, delay.fract());
+        let newer = self.buffer[(self.pos + len - whole) % len];
+        let older = self.buffer[(self.pos + len - whole - 1) % len];
+        self.pos = (self.pos + 1) % len;
+
+        Simd::from_array([l, newer + frac * (older - newer)])
+    }
+}
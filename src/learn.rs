@@ -0,0 +1,100 @@
+use nih_plug::prelude::*;
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// Stored in place of a CC number for unbound targets, CC numbers stop at 127
+const UNBOUND: u8 = u8::MAX;
+
+const NUM_CCS: u8 = 128;
+
+/// Parameters MIDI CCs can be bound to, in the order they're learned
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    Cutoff,
+    Gain,
+}
+
+const TARGETS: [Target; 2] = [Target::Cutoff, Target::Gain];
+
+/// MIDI learn, binding a controller's CCs to the cutoff and gain.
+///
+/// Arming it (with the editor's "Learn" button) clears both bindings, then, the
+/// next CC received is bound to the cutoff, and the next one with a different number,
+/// to the gain, after which learning stops. Moving the cutoff's controller around
+/// while learning is thus fine, only moving another one binds the gain.
+///
+/// The bindings are saved with the plugin's state. `process` reads and writes
+/// them, so everything in here is lock-free
+pub struct MidiMap {
+    /// each target's CC number, in `TARGETS`' order, or `UNBOUND`
+    ccs: [AtomicU8; 2],
+    /// index, in `TARGETS`, of the next target to bind, `TARGETS.len()` when not learning
+    learning: AtomicU8,
+}
+
+impl Default for MidiMap {
+    fn default() -> Self {
+        Self {
+            ccs: [AtomicU8::new(UNBOUND), AtomicU8::new(UNBOUND)],
+            learning: AtomicU8::new(TARGETS.len() as u8),
+        }
+    }
+}
+
+impl MidiMap {
+    /// Clears the bindings and starts learning new ones
+    pub fn arm(&self) {
+        for cc in &self.ccs {
+            cc.store(UNBOUND, Ordering::Relaxed);
+        }
+        self.learning.store(0, Ordering::Relaxed);
+    }
+
+    /// The target being learned, if learning
+    pub fn learning(&self) -> Option<Target> {
+        TARGETS
+            .get(self.learning.load(Ordering::Relaxed) as usize)
+            .copied()
+    }
+
+    /// CC number bound to `target`
+    pub fn cc(&self, target: Target) -> Option<u8> {
+        let cc = self.ccs[target as usize].load(Ordering::Relaxed);
+        (cc != UNBOUND).then_some(cc)
+    }
+
+    /// Handles CC number `cc` having been received, binding it if learning,
+    /// and returns the target it drives, if any
+    pub fn receive(&self, cc: u8) -> Option<Target> {
+        if let Some(target) = self.learning() {
+            let already_bound = TARGETS[..target as usize]
+                .iter()
+                .any(|&t| self.cc(t) == Some(cc));
+            if !already_bound {
+                self.ccs[target as usize].store(cc, Ordering::Relaxed);
+                self.learning.store(target as u8 + 1, Ordering::Relaxed);
+            }
+        }
+
+        TARGETS
+            .into_iter()
+            .find(|&target| self.cc(target) == Some(cc))
+    }
+}
+
+impl<'a> PersistentField<'a, [Option<u8>; 2]> for MidiMap {
+    /// Restored states binding CC numbers that can't exist leave them unbound
+    fn set(&self, new_value: [Option<u8>; 2]) {
+        for (cc, new) in self.ccs.iter().zip(new_value) {
+            let new = new.filter(|&new| new < NUM_CCS);
+            cc.store(new.unwrap_or(UNBOUND), Ordering::Relaxed);
+        }
+    }
+
+    fn map<F, R>(&self, f: F) -> R
+    where
+        F: Fn(&[Option<u8>; 2]) -> R,
+    {
+        f(&TARGETS.map(|target| self.cc(target)))
+    }
+}
@@ -0,0 +1,109 @@
+use nih_plug::prelude::*;
+
+use core::f32::consts::TAU;
+
+use crate::modulation::CENTS_PER_OCTAVE;
+
+/// Length of an LFO cycle, as a note value
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LfoRate {
+    #[name = "1/1"]
+    Whole,
+    #[name = "1/2"]
+    Half,
+    #[default]
+    #[name = "1/4"]
+    Quarter,
+    #[name = "1/8"]
+    Eighth,
+    #[name = "1/16"]
+    Sixteenth,
+    #[name = "1/32"]
+    ThirtySecond,
+}
+
+impl LfoRate {
+    /// Length of a cycle, in quarter notes, the unit of `Transport::pos_beats`
+    fn beats(self) -> f64 {
+        match self {
+            Self::Whole => 4.,
+            Self::Half => 2.,
+            Self::Quarter => 1.,
+            Self::Eighth => 0.5,
+            Self::Sixteenth => 0.25,
+            Self::ThirtySecond => 0.125,
+        }
+    }
+}
+
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LfoShape {
+    #[default]
+    Sine,
+    Triangle,
+    Square,
+}
+
+impl LfoShape {
+    /// The waveform's value, in `-1..=1`, at `phase`, in `0..1`. All of them
+    /// start the cycle rising from (or jumping up at) 0
+    fn eval(self, phase: f32) -> f32 {
+        match self {
+            Self::Sine => (TAU * phase).sin(),
+            Self::Triangle => 1. - 4. * ((phase + 0.25).fract() - 0.5).abs(),
+            Self::Square => {
+                if phase < 0.5 {
+                    1.
+                } else {
+                    -1.
+                }
+            }
+        }
+    }
+}
+
+/// Tempo-synced LFO, moving the cutoff by up to `depth` octaves, either way
+pub struct Lfo {
+    /// in cycles
+    phase: f64,
+    /// per sample, 0 when the transport is stopped
+    increment: f64,
+    shape: LfoShape,
+    depth: f32,
+}
+
+impl Lfo {
+    /// The LFO's state at the start of the current block. The phase is derived from the
+    /// transport's position, so that it stays locked to it on loops and relocations.
+    /// Returns `None` if the host doesn't report a position or tempo
+    pub fn from_transport(
+        transport: &Transport,
+        rate: LfoRate,
+        shape: LfoShape,
+        depth: f32,
+    ) -> Option<Self> {
+        let beats = transport.pos_beats()?;
+        let tempo = transport.tempo?;
+
+        let increment = if transport.playing {
+            tempo / (60. * transport.sample_rate as f64 * rate.beats())
+        } else {
+            0.
+        };
+
+        Some(Self {
+            // positions can be negative, during pre-roll
+            phase: (beats / rate.beats()).rem_euclid(1.),
+            increment,
+            shape,
+            depth,
+        })
+    }
+
+    /// Offset to apply to the cutoff frequency for the next sample, in cents
+    pub fn next_cents(&mut self) -> f32 {
+        let octaves = self.depth * self.shape.eval(self.phase as f32);
+        self.phase = (self.phase + self.increment).fract();
+        octaves * CENTS_PER_OCTAVE
+    }
+}
@@ -6,67 +6,2835 @@ use plugin_util::{
 };
 
 use nih_plug::prelude::*;
+use nih_plug_egui::EguiState;
 
-use core::f32::consts::TAU;
+use atomic_float::AtomicF32;
+
+use core::{
+    array,
+    f32::consts::{LN_10, PI, TAU},
+    f64::consts::TAU as TAU_64,
+    iter,
+    sync::atomic::Ordering,
+};
 use std::sync::Arc;
 
-const MIN_FREQ: f32 = 13.;
-const MAX_FREQ: f32 = 21000.;
+mod bank;
+mod ceiling;
+mod compare;
+mod crossover;
+mod denormal;
+mod dither;
+mod double;
+mod drift;
+mod editor;
+mod envelope;
+mod fm;
+mod gain;
+mod glide;
+mod haas;
+mod learn;
+mod lfo;
+mod matched;
+mod meters;
+mod mix;
+mod mode;
+mod modulation;
+mod monitor;
+mod morph;
+mod oversampling;
+mod peak;
+mod poly_mod;
+mod presets;
+mod quality;
+mod range;
+mod response;
+mod routing;
+mod self_osc;
+mod stage;
+mod true_peak;
+
+use bank::PresetTask;
+use ceiling::Ceiling;
+use compare::Compare;
+use crossover::Crossover;
+use denormal::{DenormalMode, Noise, ScopedFtz};
+use dither::{Dither, Ditherer};
+use double::DoubleOnePole;
+use drift::Drift;
+use envelope::Envelope;
+use fm::Fm;
+use gain::{GainLimit, GainPosition, SharedGainLimit};
+use glide::Glide;
+use haas::Haas;
+use learn::{MidiMap, Target};
+use lfo::{Lfo, LfoRate, LfoShape};
+use matched::MatchedOnePole;
+use meters::Meters;
+use mix::MixLaw;
+use mode::{seesaw_gain, Blend, Mode, Output};
+use modulation::{Modulation, CENTS_PER_OCTAVE};
+use monitor::Monitor;
+use morph::{Morph, MorphPoint};
+use oversampling::Oversampler;
+use peak::{max_lane, peak};
+use poly_mod::{PolyMod, PolyModTarget};
+use quality::Quality;
+use range::CutoffRange;
+use response::{Complex, Operating};
+
+pub use response::ResponseCurve;
+use routing::{AuxOutputs, Routing};
+use self_osc::SelfOsc;
+use stage::{Character, Stage, StageParams, Stages};
+use true_peak::TruePeakLimiter;
+
+const MIN_FREQ: f32 = 13.;
+const MAX_FREQ: f32 = 21000.;
+
+/// With key tracking, this note leaves the cutoff where the knob puts it (C4)
+const KEY_TRACK_CENTER: u8 = 60;
+
+/// MIDI CC modulating the cutoff, "brightness" (MPE's timbre dimension).
+/// CLAP hosts can send per-note brightness expressions instead
+const EXPRESSION_CC: u8 = 74;
+
+/// Number of channels processed by a single filter instance
+const LANES: usize = 2;
+/// Enough for 5.1
+const MAX_CHANNELS: usize = 6;
+const NUM_FILTERS: usize = MAX_CHANNELS.div_ceil(LANES);
+
+type Filter = OnePole<LANES>;
+
+/// Largest number of samples each channel pair processes in one go, the chunks'
+/// actual length is set by `chunk_size`. The per sample values shared by all pairs
+/// (see `Controls`) are computed beforehand, for the whole chunk, so that each pair's
+/// filters then run through it without interruption. It's also how often the
+/// coefficients are recomputed while the gain's automated (see [`UpdateRate`])
+const CHUNK: usize = 32;
+
+/// Shortest chunks `chunk_size` can be set to, below that, automated coefficients
+/// might as well be recomputed every sample (see `Quality::High`)
+const MIN_CHUNK: usize = 4;
+
+/// How often the filters' coefficients are recomputed, within a block, in order
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum UpdateRate {
+    /// Once, for the whole block, the settings are static
+    Block,
+    /// Once per chunk, for the gain's automation. The filters ramp their
+    /// coefficients towards those at the chunk's end, over the chunk
+    Chunk,
+    /// Every sample, for modulation, which can move faster than the smoothers, and
+    /// the cutoff's automation, the cutoff's smoother isn't linear, ramping it
+    /// chunk by chunk is audible on long chunks, at low cutoffs
+    Sample,
+}
+
+/// Maximum number of filters chained in series, for a 24 dB/oct slope
+const MAX_STAGES: usize = 4;
+
+/// Sample rate assumed until the host provides one
+const DEFAULT_SAMPLE_RATE: f32 = 44100.;
+
+/// Length of the crossfades between the outputs of the old and new filter modes,
+/// and in and out of bypass, and freeze
+const FADE_MS: f32 = 5.;
+
+/// Peak level of the noise added to the input with `DenormalMode::Noise`. It keeps the
+/// filters' state from decaying into the denormal range (and slowing processing down to
+/// a crawl) during long silences, while being ~400 dB below full scale
+const ANTI_DENORMAL: f32 = 1e-20;
+
+/// Cutoff of the DC blocker's highpass, low enough to leave the audible range alone
+const DC_BLOCK_FREQ: f32 = 5.;
+
+/// Cutoff of the rumble filter's highpass, the bottom of the audible range
+const RUMBLE_FREQ: f32 = 20.;
+
+/// Corner of the highpass' damping lowpass at full damping. It starts at `MAX_FREQ`,
+/// and moves down, exponentially, as damping goes up
+const MIN_DAMPING_FREQ: f32 = 2000.;
+
+/// Default frequency of the crossover, between the band outputs
+const CROSSOVER_FREQ: f32 = 1000.;
+
+/// Default smoothing time of the cutoff, gain, trim and resonance parameters
+const SMOOTHING_MS: f32 = 20.;
+/// Their smoothers, their actual smoothing time is scaled by `smoothing_ms / SMOOTHING_MS`
+static CUTOFF_SMOOTHING: SmoothingStyle = SmoothingStyle::Logarithmic(SMOOTHING_MS);
+static GAIN_SMOOTHING: SmoothingStyle = SmoothingStyle::Linear(SMOOTHING_MS);
+static RESONANCE_SMOOTHING: SmoothingStyle = SmoothingStyle::Linear(SMOOTHING_MS);
+
+/// Largest gain applied by auto gain, 12 dB
+const MAX_MAKEUP: f32 = 4.;
+
+/// Time taken by the peak meter to fall by 12 dB
+const PEAK_DECAY_MS: f32 = 150.;
+
+/// Time taken by the true peak limiter's gain reduction to recover by 63%
+const TRUE_PEAK_RELEASE_MS: f32 = 50.;
+
+/// With the vintage option on, fraction by which full scale signals pull the cutoff down
+const VINTAGE_DEPTH: f32 = 0.2;
+
+/// Loop gain at full resonance, kept below 1 so that the loop stays stable
+const MAX_FEEDBACK: f32 = 0.9;
+
+/// Largest difference between the tilt EQ's highs and lows, either way
+const MAX_SLOPE_DB: f32 = 12.;
+
+/// How far the resonance's ringing decays before the tail reported to the host ends,
+/// the output is considered silent below that
+const TAIL_DECAY_DB: f32 = 120.;
+
+/// Main input and output of `num_channels` channels each. `name` is what hosts
+/// letting users pick a layout (e. g. through CLAP's audio ports configs) show
+const fn layout(name: &'static str, num_channels: u32) -> AudioIOLayout {
+    AudioIOLayout {
+        main_input_channels: NonZeroU32::new(num_channels),
+        main_output_channels: NonZeroU32::new(num_channels),
+        names: PortNames {
+            layout: Some(name),
+            ..PortNames::const_default()
+        },
+        ..AudioIOLayout::const_default()
+    }
+}
+
+/// A single, stereo, sidechain input
+const SIDECHAIN_PORTS: &[NonZeroU32] = &[new_nonzero_u32(2)];
+
+/// The send output (see [`Routing`]), and the crossover's low and high bands
+/// (see [`Crossover`]), with as many channels as the main output
+const fn aux_output_ports(num_channels: u32) -> &'static [NonZeroU32] {
+    const MONO: &[NonZeroU32] = &[new_nonzero_u32(1); 3];
+    const STEREO: &[NonZeroU32] = &[new_nonzero_u32(2); 3];
+    const QUAD: &[NonZeroU32] = &[new_nonzero_u32(4); 3];
+    const SURROUND: &[NonZeroU32] = &[new_nonzero_u32(6); 3];
+    match num_channels {
+        1 => MONO,
+        2 => STEREO,
+        4 => QUAD,
+        6 => SURROUND,
+        _ => &[],
+    }
+}
+
+/// `layout`, with a sidechain input, driving the envelope follower, a send output,
+/// and the crossover's band outputs
+const fn aux_layout(name: &'static str, num_channels: u32) -> AudioIOLayout {
+    AudioIOLayout {
+        aux_input_ports: SIDECHAIN_PORTS,
+        aux_output_ports: aux_output_ports(num_channels),
+        names: PortNames {
+            layout: Some(name),
+            aux_inputs: &["Sidechain"],
+            aux_outputs: &["Send", "Low", "High"],
+            ..PortNames::const_default()
+        },
+        ..layout(name, num_channels)
+    }
+}
+
+/// Audio to process in place, seen as pairs of channels, each processed by its own
+/// `ChannelPair`, with one channel per SIMD lane
+trait PairedChannels {
+    fn num_samples(&self) -> usize;
+
+    fn num_channels(&self) -> usize;
+
+    fn num_pairs(&self) -> usize;
+
+    /// Reads the `samples.len()` samples of `pair`, starting at sample `start`
+    fn load(&self, pair: usize, start: usize, samples: &mut [f32x2]);
+
+    /// Writes `samples` back to where `load` got them from
+    fn store(&mut self, pair: usize, start: usize, samples: &[f32x2]);
+}
+
+/// One slice per channel, channels `2 * pair` and `2 * pair + 1` making up each pair.
+/// With odd channel counts, the last pair has no second channel, that lane is filled
+/// with silence, so that its filter state never moves away from zero, and then dropped
+impl PairedChannels for [&mut [f32]] {
+    fn num_samples(&self) -> usize {
+        self.first().map_or(0, |channel| channel.len())
+    }
+
+    fn num_channels(&self) -> usize {
+        self.len()
+    }
+
+    fn num_pairs(&self) -> usize {
+        self.len().div_ceil(LANES)
+    }
+
+    fn load(&self, pair: usize, start: usize, samples: &mut [f32x2]) {
+        for lane in 0..LANES {
+            if let Some(channel) = self.get(pair * LANES + lane) {
+                for (sample, &x) in samples.iter_mut().zip(&channel[start..]) {
+                    sample[lane] = x;
+                }
+            }
+        }
+    }
+
+    fn store(&mut self, pair: usize, start: usize, samples: &[f32x2]) {
+        for lane in 0..LANES {
+            if let Some(channel) = self.get_mut(pair * LANES + lane) {
+                for (y, sample) in channel[start..].iter_mut().zip(samples) {
+                    *y = sample[lane];
+                }
+            }
+        }
+    }
+}
+
+/// Peak absolute value, across all channels, of each of the `len` samples of
+/// `audio` starting at `start`
+fn peak_levels(audio: &(impl PairedChannels + ?Sized), start: usize, len: usize) -> [f32; CHUNK] {
+    let mut levels = [0.; CHUNK];
+    for i in 0..audio.num_pairs() {
+        let mut samples = [Simd::splat(0.); CHUNK];
+        audio.load(i, start, &mut samples[..len]);
+        for (level, sample) in levels.iter_mut().zip(&samples[..len]) {
+            *level = max_lane(sample.abs()).max(*level);
+        }
+    }
+    levels
+}
+
+/// Sum of the squares of each lane of `samples`, a run of any length. Summed in
+/// double precision, where even those of the quietest (single precision) samples
+/// are far from the denormal range
+fn energy(samples: &[f32x2]) -> f64x2 {
+    samples.iter().fold(Simd::splat(0.), |sum, x| {
+        let x = x.cast::<f64>();
+        sum + x * x
+    })
+}
+
+/// Interleaved stereo frames, a single pair
+impl PairedChannels for [[f32; 2]] {
+    fn num_samples(&self) -> usize {
+        self.len()
+    }
+
+    fn num_channels(&self) -> usize {
+        LANES
+    }
+
+    fn num_pairs(&self) -> usize {
+        1
+    }
+
+    fn load(&self, _pair: usize, start: usize, samples: &mut [f32x2]) {
+        for (sample, &frame) in samples.iter_mut().zip(&self[start..]) {
+            *sample = Simd::from_array(frame);
+        }
+    }
+
+    fn store(&mut self, _pair: usize, start: usize, samples: &[f32x2]) {
+        for (frame, sample) in self[start..].iter_mut().zip(samples) {
+            *frame = sample.to_array();
+        }
+    }
+}
+
+/// Replaces non-finite lanes of `x` with silence, so that a single NaN or infinite
+/// input sample can't poison the filters' state, or the dry signal's delay line
+fn sanitize(x: f32x2) -> f32x2 {
+    x.is_finite().select(x, Simd::splat(0.))
+}
+
+/// Maps `[l, r]` to `[l + r, l - r]`. Applying it twice doubles the input, so
+/// encoding to mid/side is `0.5 * ms_butterfly(x)`, and decoding, `ms_butterfly(x)`.
+/// Both scalings being powers of two, round trips preserve gain exactly
+fn ms_butterfly(x: f32x2) -> f32x2 {
+    x * Simd::from_array([1., -1.]) + x.reverse()
+}
+
+/// Cheap, branchless saturator, bounded to `(-1, 1)`, used to keep the resonance
+/// loop from blowing up when the filter itself has gain (e. g. shelves)
+fn soft_clip(x: f32x2) -> f32x2 {
+    x / (Simd::splat(1.) + x.abs())
+}
+
+/// Rational approximation of `tanh`, exact at 0 and reaching ±1 at ±3, where it's clamped
+fn tanh_approx(x: f32x2) -> f32x2 {
+    let x = x.simd_clamp(Simd::splat(-3.), Simd::splat(3.));
+    let x2 = x * x;
+    x * (Simd::splat(27.) + x2) / (Simd::splat(27.) + Simd::splat(9.) * x2)
+}
+
+/// Boosts `x` by `drive_db` decibels through a soft clipper, then brings it back down
+/// by the same amount, so that quiet signals keep their level, and only peaks get
+/// squashed. Returns `x` untouched when `drive_db` is 0
+fn saturate(x: f32x2, drive_db: f32) -> f32x2 {
+    if drive_db == 0. {
+        x
+    } else {
+        let drive = util::db_to_gain(drive_db);
+        tanh_approx(x * Simd::splat(drive)) / Simd::splat(drive)
+    }
+}
+
+/// Asymmetric waveshaper, adding even harmonics, the way a tube biased off center
+/// would: `x + warmth * soft_clip(x)²`, bounded, and close to `x + warmth * x²` for
+/// quiet signals. It also adds DC, so the DC blocker is on along with it.
+/// Returns `x` untouched when `warmth` is 0
+fn warm(x: f32x2, warmth: f32) -> f32x2 {
+    if warmth == 0. {
+        x
+    } else {
+        let s = soft_clip(x);
+        x + Simd::splat(warmth) * s * s
+    }
+}
+
+/// Crossfade between `dry` and `wet`, with `gains`, those of the dry and wet
+/// signals (see [`MixLaw::gains`]). Returns `dry` untouched when fully dry
+fn mix_dry_wet(dry: f32x2, wet: f32x2, (dry_gain, wet_gain): (f32, f32)) -> f32x2 {
+    if wet_gain == 0. {
+        dry
+    } else {
+        Simd::splat(dry_gain) * dry + Simd::splat(wet_gain) * wet
+    }
+}
+
+#[derive(Params)]
+pub struct OnePoleParams {
+    #[persist = "editor-state"]
+    editor_state: Arc<EguiState>,
+    /// CCs driving the cutoff and gain, see [`MidiMap`]
+    #[persist = "midi-map"]
+    midi_map: MidiMap,
+    /// kept in sync with `range_min`, `range_max` and `skew`
+    range: Arc<CutoffRange>,
+    /// see [`PolyMod`]
+    poly_mod: PolyMod,
+    /// also drives the right channel when `link` is on
+    #[id = "cutoff"]
+    cutoff_l: FloatParam,
+    #[id = "cutoff_r"]
+    cutoff_r: FloatParam,
+    #[id = "link"]
+    link: BoolParam,
+    #[id = "gain"]
+    gain: FloatParam,
+    #[id = "gain_position"]
+    gain_position: EnumParam<GainPosition>,
+    /// keeps the applied gain to cuts, or boosts, only
+    #[id = "gain_mode"]
+    gain_mode: EnumParam<GainLimit>,
+    /// compensates the loudness lost by the lowpass and highpass modes
+    #[id = "auto_gain"]
+    auto_gain: BoolParam,
+    /// turns the output down as the resonance rises, so that its peak doesn't clip,
+    /// see [`resonance_headroom`]
+    #[id = "res_headroom"]
+    resonance_headroom: BoolParam,
+    #[id = "mode"]
+    mode: EnumParam<Mode>,
+    #[id = "resonance"]
+    resonance: FloatParam,
+    /// with enough resonance, the filter rings on its own, at the cutoff,
+    /// even without input, see [`SelfOsc`]
+    #[id = "self_osc"]
+    self_osc: BoolParam,
+    #[id = "mix"]
+    mix: FloatParam,
+    #[id = "mix_law"]
+    mix_law: EnumParam<MixLaw>,
+    /// sweeps the cutoff, gain, mix and mode from snapshot A to B, see [`Morph`]
+    #[id = "morph"]
+    morph: FloatParam,
+    #[persist = "morph-points"]
+    morph_points: Morph,
+    /// see [`Compare`]
+    #[persist = "compare"]
+    compare: Compare,
+    #[id = "key_track"]
+    key_track: FloatParam,
+    /// time taken by key tracking to slide from one note to the next
+    #[id = "glide"]
+    glide: FloatParam,
+    /// in octaves, how far the cutoff moves at the extremes of `EXPRESSION_CC`
+    #[id = "expression_depth"]
+    expression_depth: FloatParam,
+    #[id = "slope"]
+    slope: IntParam,
+    /// the first `slope` of them run, minus the bypassed ones
+    #[nested(array, group = "Stage")]
+    stages: [StageParams; MAX_STAGES],
+    #[id = "drive"]
+    drive: FloatParam,
+    /// amount of even harmonics added to the filters' input, see [`warm`]
+    #[id = "warmth"]
+    warmth: FloatParam,
+    /// makes the cutoff dip slightly with the input's level, like some analog filters
+    #[id = "vintage"]
+    vintage: BoolParam,
+    /// in cents, how far the cutoff wanders around, see [`Drift`]
+    #[id = "drift"]
+    drift: FloatParam,
+    #[id = "oversampling"]
+    oversampling: IntParam,
+    #[id = "quality"]
+    quality: EnumParam<Quality>,
+    /// in samples, see `CHUNK`. Shorter chunks follow the gain's automation, and the
+    /// cutoff's, with the eco quality, more closely, at a higher CPU cost
+    #[id = "chunk_size"]
+    chunk_size: IntParam,
+    #[id = "character"]
+    character: EnumParam<Character>,
+    #[id = "bypass"]
+    bypass: BoolParam,
+    /// a glitch effect, rather than a filter: the filters stop, and their output is held,
+    /// a single sample of it, until it's released, when they pick up where they stopped
+    #[id = "freeze"]
+    freeze: BoolParam,
+    #[id = "tilt"]
+    tilt: FloatParam,
+    /// how much louder the highs are than the lows, with the tilt EQ mode, both
+    /// move by half of it, see [`Mode::TiltEq`]
+    #[id = "slope_db"]
+    slope_db: FloatParam,
+    /// the shelves' corner, and the tilts' pivot, in place of the cutoff, unless
+    /// `pivot_follow` is on, mapped by `range` as well
+    #[id = "pivot"]
+    pivot: FloatParam,
+    #[id = "pivot_follow"]
+    pivot_follow: BoolParam,
+    /// process the mid and side signals, instead of left and right, of the front
+    /// pair only, with mono, or surround layouts' other channels left as they are
+    #[id = "ms_mode"]
+    ms_mode: BoolParam,
+    /// removes DC offset, and anything below `DC_BLOCK_FREQ`, from the filters' input
+    #[id = "dc_block"]
+    dc_block: BoolParam,
+    /// removes subsonic rumble, below `RUMBLE_FREQ`, from the input, whatever the mode
+    #[id = "rumble"]
+    rumble: BoolParam,
+    /// rolls the highpass mode's top end off, see [`Self::damping_freq`]
+    #[id = "damping"]
+    damping: FloatParam,
+    /// scales the side signal, from mono, at 0, to twice as wide, at 2, of the
+    /// front pair only, like `ms_mode`
+    #[id = "width"]
+    width: FloatParam,
+    /// in ms, the right channel's delay, after the filters, see [`Haas`], of the
+    /// front pair only, like `ms_mode`
+    #[id = "haas"]
+    haas: FloatParam,
+    /// in dB, each channel's own gain, applied to the filters' input, to
+    /// correct stereo imbalances. Separate from `gain`
+    #[id = "trim_l"]
+    trim_l: FloatParam,
+    #[id = "trim_r"]
+    trim_r: FloatParam,
+    #[id = "range_min"]
+    range_min: FloatParam,
+    #[id = "range_max"]
+    range_max: FloatParam,
+    /// bends the cutoff knobs' curve, see [`CutoffRange`]
+    #[id = "skew"]
+    skew: FloatParam,
+    #[id = "lfo_rate"]
+    lfo_rate: EnumParam<LfoRate>,
+    /// in octaves
+    #[id = "lfo_depth"]
+    lfo_depth: FloatParam,
+    #[id = "lfo_shape"]
+    lfo_shape: EnumParam<LfoShape>,
+    /// in octaves, how far the cutoff moves when the input, or the sidechain
+    /// if there is one, is at full scale
+    #[id = "env_depth"]
+    env_depth: FloatParam,
+    #[id = "env_attack"]
+    env_attack: FloatParam,
+    #[id = "env_release"]
+    env_release: FloatParam,
+    /// frequency of the FM oscillator, relative to the played note's
+    #[id = "fm_ratio"]
+    fm_ratio: FloatParam,
+    /// in octaves, how far the FM oscillator moves the cutoff, either way, though,
+    /// as with any modulation, never out of `MIN_FREQ..MAX_FREQ`
+    #[id = "fm_depth"]
+    fm_depth: FloatParam,
+    /// time taken by the cutoff, gain, and trims, to reach their targets
+    #[id = "smoothing_ms"]
+    smoothing_ms: FloatParam,
+    #[id = "monitor"]
+    monitor: EnumParam<Monitor>,
+    /// flips the wet signal's polarity, in both channels
+    #[id = "invert"]
+    invert: BoolParam,
+    /// only matters when the send output is connected
+    #[id = "routing"]
+    routing: EnumParam<Routing>,
+    /// only matters when the band outputs are connected
+    #[id = "crossover"]
+    crossover: BoolParam,
+    #[id = "crossover_freq"]
+    crossover_freq: FloatParam,
+    #[id = "ceiling_mode"]
+    ceiling_mode: EnumParam<Ceiling>,
+    /// in dBFS
+    #[id = "ceiling"]
+    ceiling: FloatParam,
+    #[id = "denormal_mode"]
+    denormal_mode: EnumParam<DenormalMode>,
+    /// of the main output only, the send, and the bands, are left as is
+    #[id = "dither"]
+    dither: EnumParam<Dither>,
+}
+
+/// A frequency parameter, spanning `MIN_FREQ..MAX_FREQ`
+fn freq_param(name: &str, default: f32) -> FloatParam {
+    FloatParam::new(
+        name,
+        default,
+        FloatRange::Skewed {
+            min: MIN_FREQ,
+            max: MAX_FREQ,
+            factor: FloatRange::skew_factor(-2.4),
+        },
+    )
+    .with_unit(" Hz")
+    .with_value_to_string(formatters::v2s_f32_hz_then_khz(2))
+    .with_string_to_value(formatters::s2v_f32_hz_then_khz())
+}
+
+/// A channel's input trim, in dB, smoothed like the gain
+fn trim_param(name: &str, smoothing_scale: &Arc<AtomicF32>) -> FloatParam {
+    FloatParam::new(
+        name,
+        0.,
+        FloatRange::Linear {
+            min: -12.,
+            max: 12.,
+        },
+    )
+    .with_smoother(SmoothingStyle::OversamplingAware(
+        smoothing_scale.clone(),
+        &GAIN_SMOOTHING,
+    ))
+    .with_unit(" db")
+    .with_value_to_string(formatters::v2s_f32_rounded(2))
+}
+
+/// A cutoff knob, displaying the frequency it's mapped to by `range`
+fn cutoff_param(
+    name: &str,
+    range: &Arc<CutoffRange>,
+    smoothing_scale: &Arc<AtomicF32>,
+) -> FloatParam {
+    let to_string = formatters::v2s_f32_hz_then_khz(2);
+    let from_string = formatters::s2v_f32_hz_then_khz();
+    let (display_range, parse_range) = (range.clone(), range.clone());
+
+    // the knob's center
+    freq_param(name, (MIN_FREQ * MAX_FREQ).sqrt())
+        .with_smoother(SmoothingStyle::OversamplingAware(
+            smoothing_scale.clone(),
+            &CUTOFF_SMOOTHING,
+        ))
+        .with_value_to_string(Arc::new(move |hz| to_string(display_range.map(hz))))
+        .with_string_to_value(Arc::new(move |s| {
+            from_string(s).map(|hz| parse_range.unmap(hz))
+        }))
+}
+
+impl Default for OnePoleParams {
+    fn default() -> Self {
+        let range = Arc::new(CutoffRange::default());
+        // `smoothing_ms / SMOOTHING_MS`, kept in sync with `smoothing_ms`
+        let smoothing_scale = Arc::new(AtomicF32::new(1.));
+        // kept in sync with `gain_mode`
+        let gain_limit = Arc::new(SharedGainLimit::default());
+
+        Self {
+            editor_state: editor::default_state(),
+
+            midi_map: MidiMap::default(),
+
+            poly_mod: PolyMod::default(),
+
+            cutoff_l: cutoff_param("Left Cutoff", &range, &smoothing_scale)
+                .with_poly_modulation_id(PolyModTarget::CutoffL.id()),
+
+            cutoff_r: cutoff_param("Right Cutoff", &range, &smoothing_scale)
+                .with_poly_modulation_id(PolyModTarget::CutoffR.id()),
+
+            link: BoolParam::new("Stereo Link", true),
+
+            gain: FloatParam::new(
+                "Gain",
+                0.,
+                FloatRange::Linear {
+                    min: -30.,
+                    max: 30.,
+                },
+            )
+            .with_smoother(SmoothingStyle::OversamplingAware(
+                smoothing_scale.clone(),
+                &GAIN_SMOOTHING,
+            ))
+            .with_unit(" db")
+            // shows the gain actually applied
+            .with_value_to_string({
+                let (to_string, gain_limit) = (formatters::v2s_f32_rounded(2), gain_limit.clone());
+                Arc::new(move |db| to_string(gain_limit.get().clamp(db)))
+            }),
+
+            gain_mode: EnumParam::new("Gain Mode", GainLimit::default()).with_callback({
+                let gain_limit = gain_limit.clone();
+                Arc::new(move |limit| gain_limit.set(limit))
+            }),
+
+            gain_position: EnumParam::new("Gain Position", GainPosition::default()),
+
+            auto_gain: BoolParam::new("Auto Gain", false),
+
+            resonance_headroom: BoolParam::new("Resonance Headroom", false),
+
+            mode: EnumParam::new("Filter Mode", Mode::default()),
+
+            resonance: FloatParam::new("Resonance", 0., FloatRange::Linear { min: 0., max: 1. })
+                .with_smoother(SmoothingStyle::OversamplingAware(
+                    smoothing_scale.clone(),
+                    &RESONANCE_SMOOTHING,
+                ))
+                .with_poly_modulation_id(PolyModTarget::Resonance.id()),
+
+            self_osc: BoolParam::new("Self-Oscillation", false),
+
+            mix: FloatParam::new("Mix", 1., FloatRange::Linear { min: 0., max: 1. })
+                .with_smoother(SmoothingStyle::Linear(10.))
+                .with_unit(" %")
+                .with_value_to_string(formatters::v2s_f32_percentage(0))
+                .with_string_to_value(formatters::s2v_f32_percentage()),
+
+            mix_law: EnumParam::new("Mix Law", MixLaw::default()),
+
+            // what it drives is already smoothed
+            morph: FloatParam::new("Morph", 0., FloatRange::Linear { min: 0., max: 1. })
+                .with_unit(" %")
+                .with_value_to_string(formatters::v2s_f32_percentage(0))
+                .with_string_to_value(formatters::s2v_f32_percentage()),
+
+            morph_points: Morph::default(),
+            compare: Compare::default(),
+
+            key_track: FloatParam::new("Key Tracking", 0., FloatRange::Linear { min: 0., max: 1. })
+                .with_unit(" %")
+                .with_value_to_string(formatters::v2s_f32_percentage(0))
+                .with_string_to_value(formatters::s2v_f32_percentage()),
+
+            glide: FloatParam::new(
+                "Glide",
+                0.,
+                FloatRange::Skewed {
+                    min: 0.,
+                    max: 2000.,
+                    factor: FloatRange::skew_factor(-2.),
+                },
+            )
+            .with_unit(" ms"),
+
+            expression_depth: FloatParam::new(
+                "Expression Depth",
+                2.,
+                FloatRange::Linear { min: 0., max: 5. },
+            )
+            .with_unit(" oct"),
+
+            slope: IntParam::new(
+                "Slope",
+                1,
+                IntRange::Linear {
+                    min: 1,
+                    max: MAX_STAGES as i32,
+                },
+            )
+            .with_value_to_string(Arc::new(|stages| format!("{} dB/oct", stages * 6))),
+
+            stages: array::from_fn(|i| StageParams::new(i + 1)),
+
+            drive: FloatParam::new("Drive", 0., FloatRange::Linear { min: 0., max: 24. })
+                .with_smoother(SmoothingStyle::Linear(20.))
+                .with_unit(" db"),
+
+            warmth: FloatParam::new("Warmth", 0., FloatRange::Linear { min: 0., max: 1. })
+                .with_smoother(SmoothingStyle::Linear(20.))
+                .with_unit(" %")
+                .with_value_to_string(formatters::v2s_f32_percentage(0))
+                .with_string_to_value(formatters::s2v_f32_percentage()),
+
+            vintage: BoolParam::new("Vintage", false),
+
+            drift: FloatParam::new("Drift", 0., FloatRange::Linear { min: 0., max: 100. })
+                .with_unit(" cents"),
+
+            oversampling: IntParam::new(
+                "Oversampling",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: oversampling::MAX_FACTOR_LOG2 as i32,
+                },
+            )
+            .with_value_to_string(Arc::new(|factor_log2| format!("{}x", 1 << factor_log2))),
+
+            quality: EnumParam::new("Quality", Quality::default()),
+
+            chunk_size: IntParam::new(
+                "Update Interval",
+                CHUNK as i32,
+                IntRange::Linear {
+                    min: MIN_CHUNK as i32,
+                    max: CHUNK as i32,
+                },
+            )
+            .with_unit(" samples"),
+
+            character: EnumParam::new("Character", Character::default()),
+
+            bypass: BoolParam::new("Bypass", false).make_bypass(),
+
+            freeze: BoolParam::new("Freeze", false),
+
+            tilt: FloatParam::new("Tilt", 0.5, FloatRange::Linear { min: 0., max: 1. })
+                .with_smoother(SmoothingStyle::Linear(20.)),
+
+            slope_db: FloatParam::new(
+                "Tilt EQ Slope",
+                0.,
+                FloatRange::Linear {
+                    min: -MAX_SLOPE_DB,
+                    max: MAX_SLOPE_DB,
+                },
+            )
+            .with_unit(" db")
+            .with_step_size(0.1)
+            .with_smoother(SmoothingStyle::Linear(20.)),
+
+            pivot: cutoff_param("Pivot", &range, &smoothing_scale),
+
+            // like before there was a pivot knob
+            pivot_follow: BoolParam::new("Pivot Follows Cutoff", true),
+
+            ms_mode: BoolParam::new("Mid/Side", false),
+
+            dc_block: BoolParam::new("DC Block", false),
+
+            rumble: BoolParam::new("Rumble Filter", false),
+
+            damping: FloatParam::new("Damping", 0., FloatRange::Linear { min: 0., max: 1. })
+                .with_unit(" %")
+                .with_value_to_string(formatters::v2s_f32_percentage(0))
+                .with_string_to_value(formatters::s2v_f32_percentage()),
+
+            width: FloatParam::new("Width", 1., FloatRange::Linear { min: 0., max: 2. })
+                .with_smoother(SmoothingStyle::Linear(20.))
+                .with_unit(" %")
+                .with_value_to_string(formatters::v2s_f32_percentage(0))
+                .with_string_to_value(formatters::s2v_f32_percentage()),
+
+            haas: FloatParam::new(
+                "Haas Delay",
+                0.,
+                FloatRange::Linear {
+                    min: 0.,
+                    max: haas::MAX_DELAY_MS,
+                },
+            )
+            // slow enough that sweeping across the whole range only bends the pitch,
+            // rather than reversing it
+            .with_smoother(SmoothingStyle::Linear(100.))
+            .with_unit(" ms")
+            .with_step_size(0.1),
+
+            trim_l: trim_param("Left Trim", &smoothing_scale),
+
+            trim_r: trim_param("Right Trim", &smoothing_scale),
+
+            range_min: freq_param("Range Min", MIN_FREQ).with_callback({
+                let range = range.clone();
+                Arc::new(move |min| range.set_min(min))
+            }),
+
+            range_max: freq_param("Range Max", MAX_FREQ).with_callback({
+                let range = range.clone();
+                Arc::new(move |max| range.set_max(max))
+            }),
+
+            skew: FloatParam::new("Cutoff Skew", 1., FloatRange::Linear { min: 0.5, max: 3. })
+                .with_value_to_string(formatters::v2s_f32_rounded(2))
+                .with_callback({
+                    let range = range.clone();
+                    Arc::new(move |skew| range.set_skew(skew))
+                }),
+
+            lfo_rate: EnumParam::new("LFO Rate", LfoRate::default()),
+
+            lfo_depth: FloatParam::new("LFO Depth", 0., FloatRange::Linear { min: 0., max: 4. })
+                .with_unit(" oct"),
+
+            lfo_shape: EnumParam::new("LFO Shape", LfoShape::default()),
+
+            env_depth: FloatParam::new(
+                "Envelope Depth",
+                0.,
+                FloatRange::Linear { min: -4., max: 4. },
+            )
+            .with_unit(" oct"),
+
+            env_attack: FloatParam::new(
+                "Envelope Attack",
+                5.,
+                FloatRange::Skewed {
+                    min: 0.1,
+                    max: 100.,
+                    factor: FloatRange::skew_factor(-2.),
+                },
+            )
+            .with_unit(" ms")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+
+            env_release: FloatParam::new(
+                "Envelope Release",
+                100.,
+                FloatRange::Skewed {
+                    min: 1.,
+                    max: 1000.,
+                    factor: FloatRange::skew_factor(-2.),
+                },
+            )
+            .with_unit(" ms")
+            .with_value_to_string(formatters::v2s_f32_rounded(0)),
+
+            fm_ratio: FloatParam::new(
+                "FM Ratio",
+                1.,
+                FloatRange::Skewed {
+                    min: 0.25,
+                    max: 16.,
+                    factor: FloatRange::skew_factor(-2.),
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            fm_depth: FloatParam::new("FM Depth", 0., FloatRange::Linear { min: 0., max: 4. })
+                .with_unit(" oct"),
+
+            smoothing_ms: FloatParam::new(
+                "Smoothing",
+                SMOOTHING_MS,
+                FloatRange::Skewed {
+                    min: 0.,
+                    max: 500.,
+                    factor: FloatRange::skew_factor(-2.),
+                },
+            )
+            .with_unit(" ms")
+            .with_callback({
+                let smoothing_scale = smoothing_scale.clone();
+                Arc::new(move |ms| smoothing_scale.store(ms / SMOOTHING_MS, Ordering::Relaxed))
+            }),
+
+            monitor: EnumParam::new("Monitor", Monitor::default()),
+
+            invert: BoolParam::new("Invert", false),
+
+            routing: EnumParam::new("Routing", Routing::default()),
+
+            crossover: BoolParam::new("Crossover", false),
+
+            crossover_freq: freq_param("Crossover Frequency", CROSSOVER_FREQ)
+                .with_smoother(SmoothingStyle::Logarithmic(SMOOTHING_MS)),
+
+            ceiling_mode: EnumParam::new("Ceiling Mode", Ceiling::default()),
+
+            ceiling: FloatParam::new("Ceiling", 0., FloatRange::Linear { min: -24., max: 0. })
+                .with_unit(" dBFS"),
+
+            denormal_mode: EnumParam::new("Denormals", DenormalMode::default()),
+
+            dither: EnumParam::new("Dither", Dither::default()),
+
+            range,
+        }
+    }
+}
+
+/// Factor applied to every stage's cutoff, so that the -3 dB point of a cascade of
+/// `num_stages` identical filters stays at the frequency set by the cutoff knob.
+///
+/// `n` one-pole lowpasses with cutoff `w` are 3 dB down at `w * sqrt(2^(1/n) - 1)`,
+/// highpasses at the inverse of that. The other modes have no -3 dB point, and are
+/// left alone
+fn slope_compensation(mode: Mode, num_stages: usize) -> f32 {
+    let k = ((num_stages as f32).recip().exp2() - 1.).sqrt();
+    match mode {
+        Mode::Lowpass => k.recip(),
+        Mode::Highpass => k,
+        _ => 1.,
+    }
+}
+
+/// Converts a gain, in decibels, to the linear gain of both lanes
+fn linear_gain(db: f32) -> f32x2 {
+    Simd::splat(10f32.powf(db * (1. / 20.)))
+}
+
+/// Gain making up for the loudness lost by filtering a pink-ish signal, with
+/// roughly equal energy in every octave, with a cutoff of `freq` Hz. Such a signal
+/// loses about as much power as the fraction of the `MIN_FREQ..MAX_FREQ` octaves
+/// that is filtered out. Modes keeping the whole spectrum are left alone
+fn auto_makeup(mode: Mode, freq: f32) -> f32 {
+    let t = ((freq / MIN_FREQ).ln() / (MAX_FREQ / MIN_FREQ).ln()).clamp(0., 1.);
+    let kept = match mode {
+        Mode::Lowpass => t,
+        Mode::Highpass => 1. - t,
+        _ => return 1.,
+    };
+    kept.max(MAX_MAKEUP.powi(-2)).sqrt().recip()
+}
+
+/// Gain bringing the resonance's peak, at most `1 / (1 - k)` above the passband,
+/// `k` being the loop's `feedback` amount, back down to the passband's level, see
+/// [`OnePoleParams::get_values`]. Unlike auto gain, the whole output is turned down,
+/// by up to 20 dB at full resonance, it's there to keep the peak from clipping
+fn resonance_headroom(feedback: f32) -> f32 {
+    1. - feedback
+}
+
+/// Maps the cutoffs of both lanes, in Hz, to angular frequencies, in radians per
+/// sample, scaled by `freq_ratio`, and kept in the `MIN_FREQ..MAX_FREQ` range
+fn angular_cutoff(pi_tick: f32, cutoffs: [f32; 2], freq_ratio: f32) -> f32x2 {
+    let freq = Simd::from_array(cutoffs) * Simd::splat(freq_ratio);
+    Simd::splat(pi_tick) * freq.simd_clamp(Simd::splat(MIN_FREQ), Simd::splat(MAX_FREQ))
+}
+
+impl OnePoleParams {
+    /// Brings every parameter back within its range, restored states may come from
+    /// older versions, be corrupt, or hand edited. Values that aren't even numbers
+    /// are reset to their defaults. Setting them again also runs their callbacks,
+    /// bringing the state they're mirrored in up to date
+    fn sanitize(&self) {
+        for (_, param, _) in self.param_map() {
+            // SAFETY: only called before processing, when nothing else is touching them
+            unsafe {
+                let value = param.unmodulated_normalized_value();
+                let sane = if value.is_nan() {
+                    param.default_normalized_value()
+                } else {
+                    value.clamp(0., 1.)
+                };
+                param.set_normalized_value(sane);
+            }
+        }
+    }
+
+    /// Offset, in cents, by which the cutoff frequency follows `note`, relative to
+    /// `KEY_TRACK_CENTER`. At full tracking, the cutoff moves by the same
+    /// interval as the note. With no note played yet, the knob value is used as is.
+    /// `note` is fractional, as it glides from one note to the next (see [`Glide`])
+    fn key_cents(&self, note: Option<f32>) -> f32 {
+        note.map_or(0., |note| {
+            (note - f32::from(KEY_TRACK_CENTER)) * 100. * self.key_track.value()
+        })
+    }
+
+    /// Maps the left and right knobs' values, plus their polyphonic modulation, to
+    /// the lanes' cutoffs, in Hz. When linked, both follow the left one. The pivot
+    /// knob's value `pivot` drives both instead, see [`Self::pivot_override`]
+    fn map_cutoffs(&self, l: f32, r: f32, pivot: f32) -> [f32; 2] {
+        if self.pivot_override() {
+            return [self.range.map(pivot); 2];
+        }
+        let l = self.range.map(
+            self.poly_mod
+                .apply(PolyModTarget::CutoffL, &self.cutoff_l, l),
+        );
+        if self.link.value() {
+            [l, l]
+        } else {
+            let r = self
+                .poly_mod
+                .apply(PolyModTarget::CutoffR, &self.cutoff_r, r);
+            [l, self.range.map(r)]
+        }
+    }
+
+    /// Whether the pivot knob sets the cutoff, rather than the cutoff knobs, see
+    /// [`Mode::pivots`]
+    fn pivot_override(&self) -> bool {
+        !self.pivot_follow.value() && self.current_mode().pivots()
+    }
+
+    /// The resonance knob's value `plain`, plus its polyphonic modulation
+    fn modulated_resonance(&self, plain: f32) -> f32 {
+        self.poly_mod
+            .apply(PolyModTarget::Resonance, &self.resonance, plain)
+    }
+
+    /// The lanes' current cutoffs, in Hz. They're read from the smoothers, which
+    /// MIDI learned CCs drive as well, without moving the knobs
+    fn cutoffs(&self) -> [f32; 2] {
+        self.map_cutoffs(
+            self.cutoff_l.smoothed.previous_value(),
+            self.cutoff_r.smoothed.previous_value(),
+            self.pivot.smoothed.previous_value(),
+        )
+    }
+
+    /// Moves the parameter bound to `target` to `value`, a CC's, in `0..=1`. The host
+    /// isn't told, so it's applied to the parameters' smoothers only, until they're
+    /// next automated, or the knobs moved
+    fn apply_cc(&self, target: Target, value: f32, sample_rate: f32) {
+        let params: &[&FloatParam] = match target {
+            Target::Cutoff => &[&self.cutoff_l, &self.cutoff_r],
+            Target::Gain => &[&self.gain],
+        };
+        for param in params {
+            param
+                .smoothed
+                .set_target(sample_rate, param.preview_plain(value));
+        }
+    }
+
+    /// Corner of the lowpass damping the highpass mode, in Hz, `None` in other
+    /// modes, or without damping
+    fn damping_freq(&self) -> Option<f32> {
+        let damping = self.damping.value();
+        (self.current_mode() == Mode::Highpass && damping > 0.)
+            .then(|| MAX_FREQ * (MIN_DAMPING_FREQ / MAX_FREQ).powf(damping))
+    }
+
+    /// The plugin's response, at each of `freqs`, in Hz, see
+    /// [`OnePoleFilter::magnitude_response`]. It's the left channel's, with its
+    /// own cutoff and trim, the right one's may differ when they're set apart
+    fn response(&self, at: Operating, freqs: &[f32]) -> Vec<Complex> {
+        let Operating {
+            sample_rate,
+            pi_tick,
+            freq_ratio,
+        } = at;
+        let Values {
+            w_c,
+            gain,
+            feedback,
+            compensation,
+            makeup,
+            headroom,
+            mode,
+            ..
+        } = self.get_values(pi_tick, freq_ratio);
+
+        let (filter_gain, pre_gain, post_gain) = self.gain_position.value().split(mode, gain);
+        let character = self.character.value();
+        let blend = self.blend();
+        let num_stages = self.num_active_stages();
+        let monitor = self.monitor.value();
+
+        let mix = if self.bypass.value() {
+            0.
+        } else if monitor != Monitor::Normal {
+            1.
+        } else {
+            self.mix.unmodulated_plain_value()
+        };
+        let (dry_gain, wet_gain) = self.mix_law.value().gains(mix);
+
+        let real = |x: f32x2| Complex::real(x[0].into());
+        let sample_rate = f64::from(sample_rate);
+
+        freqs
+            .iter()
+            .map(|&freq| {
+                let delay = Complex::delay(f64::from(pi_tick * freq));
+                let stage = response::stage(character, mode, w_c[0], filter_gain[0], blend, delay);
+                let cascade = (0..num_stages).fold(Complex::ONE, |h, _| h * stage);
+
+                // both run at the host's rate, like the damper
+                let host_delay = Complex::delay(TAU_64 * f64::from(freq) / sample_rate);
+                let highpass = |cutoff: f32| {
+                    let w_c = TAU_64 * f64::from(cutoff) / sample_rate;
+                    Complex::ONE - response::bilinear_lowpass(w_c, host_delay)
+                };
+                let trim = Simd::splat(util::db_to_gain(self.trim_l.unmodulated_plain_value()));
+                let mut input = real(pre_gain * compensation * trim);
+                if self.dc_blocked() {
+                    input = input * highpass(DC_BLOCK_FREQ);
+                }
+                if self.rumble.value() {
+                    input = input * highpass(RUMBLE_FREQ);
+                }
+
+                let mut filtered = input * cascade
+                    / (Complex::ONE + real(feedback) * delay * cascade)
+                    * real(post_gain);
+                if let Some(damping_freq) = self.damping_freq() {
+                    let w_c = TAU_64 * f64::from(damping_freq) / sample_rate;
+                    filtered = filtered * response::bilinear_lowpass(w_c, host_delay);
+                }
+
+                let mut wet = match monitor {
+                    Monitor::Normal => filtered * real(makeup * headroom),
+                    Monitor::Band => filtered,
+                    Monitor::Rejected => Complex::ONE - filtered,
+                };
+                if self.invert.value() {
+                    wet = Complex::real(-1.) * wet;
+                }
+
+                Complex::real(dry_gain.into()) + Complex::real(wet_gain.into()) * wet
+            })
+            .collect()
+    }
+
+    /// See [`OnePoleFilter::response_curve`]. The editor calls it with the latest
+    /// operating state the audio thread published, off the audio thread
+    fn response_curve(&self, at: Operating, num_freqs: usize, with_phase: bool) -> ResponseCurve {
+        let freqs = response::log_spaced(num_freqs, MIN_FREQ, MAX_FREQ.min(at.sample_rate / 2.));
+        let response = self.response(at, &freqs);
+        ResponseCurve {
+            magnitudes: response.iter().map(|h| h.norm() as f32).collect(),
+            phases: with_phase.then(|| response.iter().map(|h| h.arg() as f32).collect()),
+            freqs,
+        }
+    }
+
+    /// Whether any of the parameters shaping the filters' tail is still gliding
+    fn smoothing(&self) -> bool {
+        [
+            &self.cutoff_l,
+            &self.cutoff_r,
+            &self.resonance,
+            &self.gain,
+            &self.mix,
+            &self.slope_db,
+            &self.pivot,
+        ]
+        .iter()
+        .any(|param| param.smoothed.is_smoothing())
+    }
+
+    /// Whether the DC blocker's output is used, always with some warmth, which adds DC
+    fn dc_blocked(&self) -> bool {
+        self.dc_block.value() || self.warmth.value() > 0. || self.warmth.smoothed.is_smoothing()
+    }
+
+    fn num_stages(&self) -> usize {
+        self.slope.value() as usize
+    }
+
+    /// Which of the stages run: the first `num_stages`, unless bypassed. If they're
+    /// all bypassed, the first one runs anyway, there's no slope without a stage
+    fn active_stages(&self) -> [bool; MAX_STAGES] {
+        let num_stages = self.num_stages();
+        let mut active = array::from_fn(|i| i < num_stages && self.stages[i].enabled.value());
+        if !active.contains(&true) {
+            active[0] = true;
+        }
+        active
+    }
+
+    /// Number of stages the signal actually goes through, setting the slope
+    fn num_active_stages(&self) -> usize {
+        self.active_stages().iter().filter(|&&on| on).count()
+    }
+
+    /// As chosen, unless overridden by `quality`
+    fn oversampling_log2(&self) -> usize {
+        let chosen = self.oversampling.value() as usize;
+        self.quality.value().oversampling_log2(chosen)
+    }
+
+    /// Offset, in cents, by which note expressions move the cutoff frequency. `expression`
+    /// goes from 0 to 1, its center leaves the cutoff alone, and each end moves it
+    /// by `expression_depth` octaves, down or up. Before any expression is received,
+    /// the knob value is used as is
+    fn expression_cents(&self, expression: Option<f32>) -> f32 {
+        expression.map_or(0., |e| {
+            (e * 2. - 1.) * self.expression_depth.value() * CENTS_PER_OCTAVE
+        })
+    }
+
+    /// The mode knob's mode, unless the morph has moved it elsewhere since
+    fn current_mode(&self) -> Mode {
+        let knob_mode = self.mode.value();
+        self.morph_points.mode(knob_mode).unwrap_or(knob_mode)
+    }
+
+    /// The knobs' values, for the morph to sweep from, or to
+    fn morph_point(&self) -> MorphPoint {
+        MorphPoint {
+            cutoff: self.cutoff_l.unmodulated_plain_value(),
+            gain: self.gain.unmodulated_plain_value(),
+            mix: self.mix.unmodulated_plain_value(),
+            mode: self.mode.unmodulated_plain_value(),
+        }
+    }
+
+    /// Factor by which the cutoff frequency is corrected, see [`slope_compensation`],
+    /// and, with the tilt EQ, moved onto the pivot, see [`seesaw_gain`]
+    fn slope_ratio(&self) -> f32 {
+        let mode = self.current_mode();
+        let compensation = slope_compensation(mode, self.num_active_stages());
+        if mode == Mode::TiltEq {
+            compensation * self.seesaw()
+        } else {
+            compensation
+        }
+    }
+
+    /// Each stage's seesaw gain, see [`Blend::seesaw`]
+    fn seesaw(&self) -> f32 {
+        seesaw_gain(
+            self.slope_db.smoothed.previous_value(),
+            self.num_active_stages(),
+        )
+    }
+
+    fn blend(&self) -> Blend {
+        Blend {
+            tilt: self.tilt.unmodulated_plain_value(),
+            seesaw: self.seesaw(),
+        }
+    }
+
+    /// The filters' current settings, see [`Values`].
+    ///
+    /// The feedback amount `k` is `resonance * MAX_FEEDBACK`, and the previous output
+    /// sample is subtracted from the input, scaled by `k`. A single pole can't ring on
+    /// its own, the extra phase shift comes from the one sample delay in the loop, so the
+    /// peak sits where the loop's phase reaches 180°, above the cutoff, closer to it as
+    /// the cutoff rises. The peak's gain is at most `1 / (1 - k)`, so, the Q of the
+    /// equivalent resonator is roughly `1 / (1 - k)`, going from 1 up to 10.
+    ///
+    /// Near DC, the feedback is negative instead, there, the filters' gain `h` is brought
+    /// down to `h / (1 + k h)`, so the loop's input is scaled up by `1 + k h`,
+    /// which keeps the low end in place (e. g. a lowpass' passband) while the peak rises.
+    ///
+    /// `freq_ratio` scales the cutoff frequency (see [`OnePoleFilter::freq_ratio`])
+    fn get_values(&self, pi_tick: f32, freq_ratio: f32) -> Values {
+        let mode = self.current_mode();
+        let w_c = angular_cutoff(pi_tick, self.cutoffs(), freq_ratio);
+        let gain_db = self.gain.smoothed.previous_value();
+        let gain = linear_gain(self.gain_mode.value().clamp(gain_db));
+        let resonance = self.modulated_resonance(self.resonance.unmodulated_plain_value());
+        let feedback = Simd::splat(resonance * MAX_FEEDBACK);
+
+        let (filter_gain, ..) = self.gain_position.value().split(mode, gain);
+        let stage_dc_gain = mode.dc_gain(filter_gain, self.blend());
+        let dc_gain = (0..self.num_active_stages()).fold(Simd::splat(1.), |g, _| g * stage_dc_gain);
+        let compensation = Simd::splat(1.) + feedback * dc_gain;
+
+        let makeup = if self.auto_gain.value() {
+            // the cascade's -3 dB point, rather than each stage's cutoff
+            let compensation = slope_compensation(mode, self.num_active_stages());
+            let freq = w_c / Simd::splat(pi_tick * compensation);
+            freq.to_array().map(|f| auto_makeup(mode, f))
+        } else {
+            [1.; 2]
+        };
+
+        let headroom = if self.resonance_headroom.value() {
+            resonance_headroom(resonance * MAX_FEEDBACK)
+        } else {
+            1.
+        };
+
+        Values {
+            w_c,
+            gain,
+            feedback,
+            compensation,
+            dc_gain,
+            makeup: Simd::from_array(makeup),
+            headroom: Simd::splat(headroom),
+            mode,
+        }
+    }
+}
+
+/// Returned by [`OnePoleParams::get_values`]
+struct Values {
+    /// angular cutoff
+    w_c: f32x2,
+    /// linear
+    gain: f32x2,
+    feedback: f32x2,
+    /// gain applied to the resonance loop's input
+    compensation: f32x2,
+    /// the filters' gain at DC, which `compensation` is computed from
+    dc_gain: f32x2,
+    /// auto gain's factor (see [`auto_makeup`]), 1 when it's off
+    makeup: f32x2,
+    /// see [`resonance_headroom`], 1 when it's off. While processing, it's
+    /// recomputed every sample, following the resonance's smoother
+    headroom: f32x2,
+    mode: Mode,
+}
+
+/// Everything needed to process a pair of channels
+#[derive(Default)]
+struct ChannelPair {
+    /// filters in series, only the first `OnePoleParams::num_stages` are used
+    stages: Stages,
+    /// previous output, fed back into the first stage's input
+    last_output: f32x2,
+    /// the filters' output, held while frozen, it follows `last_output` otherwise
+    frozen: f32x2,
+    /// highpass filtering the input when `dc_block` is on, or with some warmth. It
+    /// keeps running when it's off, so that turning it on doesn't bring a transient in
+    dc_blocker: Filter,
+    /// highpass filtering the input when `rumble` is on, also kept running
+    rumble_filter: Filter,
+    /// lowpass filtering the output, when the highpass mode is damped, also kept running
+    damper: Filter,
+    oversampler: Oversampler,
+    /// added to the input, with `DenormalMode::Noise`
+    noise: Noise,
+    /// fed into the resonance loop, when `self_osc` is on
+    self_osc: SelfOsc,
+    /// only runs with `Ceiling::TruePeak`
+    limiter: TruePeakLimiter,
+    /// only runs when its band outputs are connected, and it's on
+    crossover: Crossover,
+    /// keeps running without any delay
+    haas: Haas,
+    ditherer: Ditherer,
+}
+
+/// Values staying the same throughout a whole block
+struct BlockSettings<F> {
+    oversampling_log2: usize,
+    /// stages in the cascade, bypassed ones included, only the coefficients of
+    /// those of `active_stages` are kept up to date
+    num_stages: usize,
+    /// see [`OnePoleParams::active_stages`]
+    active_stages: [bool; MAX_STAGES],
+    /// the last of `active_stages`
+    last_stage: usize,
+    update: fn(&mut F, f32x2, f32x2, usize),
+    output: Output<F>,
+    /// output of the mode we're fading away from
+    prev_output: Output<F>,
+    /// linear, used unless it's recomputed every sample (see `Controls::coefficients`)
+    gain: f32x2,
+    gain_position: GainPosition,
+    /// the current mode, the gain's position depends on it
+    mode: Mode,
+    /// see [`Values::dc_gain`]
+    dc_gain: f32x2,
+    makeup: f32x2,
+    /// whether the output is turned down with the resonance, see [`resonance_headroom`]
+    headroom: bool,
+    ms_mode: bool,
+    dc_block: bool,
+    rumble: bool,
+    /// whether `ChannelPair::damper`'s output is used
+    damp: bool,
+    /// whether the cutoff follows each lane's level, see `VINTAGE_DEPTH`
+    vintage: bool,
+    monitor: Monitor,
+    invert: bool,
+    /// `Routing::Main` without a send output
+    routing: Routing,
+    /// whether the crossover splits the output into bands, off without band outputs
+    crossover: bool,
+    ceiling_mode: Ceiling,
+    /// linear
+    ceiling: f32,
+    /// see [`TruePeakLimiter::process`]
+    true_peak_release: f32,
+    denormal_mode: DenormalMode,
+    dither: Dither,
+    /// whether to measure the output's peak level
+    metering: bool,
+}
+
+/// Per sample values shared by all channel pairs, for one chunk
+struct Controls {
+    /// dry and wet gains, already scaled down by bypass, and always fully
+    /// wet while monitoring
+    mix: [(f32, f32); CHUNK],
+    drive: [f32; CHUNK],
+    warmth: [f32; CHUNK],
+    tilt: [f32; CHUNK],
+    /// see [`Blend::seesaw`]
+    seesaw: [f32; CHUNK],
+    /// resonance loop's feedback amount
+    feedback: [f32; CHUNK],
+    width: [f32; CHUNK],
+    /// in samples, see [`OnePoleParams::haas`]
+    haas: [f32; CHUNK],
+    /// linear, of both lanes, see [`OnePoleParams::trim_l`]
+    trim: [f32x2; CHUNK],
+    /// gain of the wet signal, dipping down to 0 around soft resets
+    reset_fade: [f32; CHUNK],
+    /// weight of the previous mode's output
+    fade: [f32; CHUNK],
+    /// weight of the held output, 1 when fully frozen
+    freeze: [f32; CHUNK],
+    /// angular cutoffs and linear gains, in case they're recomputed every sample
+    coefficients: Option<[(f32x2, f32x2); CHUNK]>,
+    /// linear gains, in case the coefficients are recomputed every chunk, the
+    /// gain applied before or after the filters still moves every sample
+    gains: Option<[f32x2; CHUNK]>,
+    /// the self-oscillation's angular frequencies, at the oversampled rate,
+    /// and linear levels, when it's on
+    self_osc: Option<[(f32x2, f32); CHUNK]>,
+}
+
+/// Everything the coefficients were last updated for, with once-per-block updates
+#[derive(Clone, Copy, PartialEq)]
+struct Targets {
+    w_c: f32x2,
+    /// the filters' own gain
+    gain: f32x2,
+    mode: Mode,
+    character: Character,
+    num_stages: usize,
+    /// un-bypassed stages still have their old coefficients
+    active_stages: [bool; MAX_STAGES],
+    num_filters: usize,
+}
+
+/// Where a pair sits in the host's channel layout
+#[derive(Clone, Copy)]
+struct PairLayout {
+    /// whether it's the front left and right pair, with both channels there, the
+    /// mid/side processing, the width, and the Haas delay only apply to that one
+    stereo: bool,
+    /// how many of its channels are there, the others are left out of the output meters
+    lanes: usize,
+}
+
+/// A chunk's worth of the auxiliary outputs' samples, see [`AuxOutputs`]
+struct AuxChunk {
+    send: [f32x2; CHUNK],
+    low: [f32x2; CHUNK],
+    high: [f32x2; CHUNK],
+}
+
+/// What the editor gets to see of a block
+#[derive(Default)]
+struct BlockMeters {
+    ceiling_hit: bool,
+    /// of each lane, see [`Meters`]
+    input_peaks: f32x2,
+    output_peaks: f32x2,
+    /// sum of the output's squared samples, of all pairs, the same output as
+    /// `output_peaks`
+    output_energy: f64x2,
+}
+
+impl ChannelPair {
+    /// The `index`-th pair, dithered with noise of its own, see [`Ditherer::new`]
+    fn new(index: usize) -> Self {
+        Self {
+            ditherer: Ditherer::new(index),
+            ..Default::default()
+        }
+    }
+
+    fn reset(&mut self) {
+        self.stages.reset();
+        self.last_output = Simd::splat(0.);
+        self.frozen = Simd::splat(0.);
+        self.dc_blocker.reset();
+        self.rumble_filter.reset();
+        self.damper.reset();
+        self.oversampler.reset();
+        self.self_osc.reset();
+        self.limiter.reset();
+        self.crossover.reset();
+        self.haas.reset();
+        self.ditherer.reset();
+    }
+
+    /// Filters `samples`, a chunk's worth of at most `CHUNK` samples, in place,
+    /// and writes the auxiliary outputs' samples to `aux`
+    fn process_chunk<F: Stage>(
+        &mut self,
+        layout: PairLayout,
+        block: &BlockSettings<F>,
+        controls: &Controls,
+        samples: &mut [f32x2],
+        aux: &mut AuxChunk,
+        meters: &mut BlockMeters,
+    ) {
+        let BlockSettings {
+            oversampling_log2,
+            num_stages,
+            active_stages,
+            last_stage,
+            update,
+            output,
+            prev_output,
+            gain,
+            gain_position,
+            mode,
+            dc_gain,
+            makeup,
+            headroom,
+            ms_mode,
+            dc_block,
+            rumble,
+            damp,
+            vintage,
+            monitor,
+            invert,
+            routing,
+            crossover,
+            ceiling_mode,
+            ceiling,
+            true_peak_release,
+            denormal_mode,
+            dither,
+            metering,
+        } = *block;
+        let PairLayout { stereo, lanes } = layout;
+        let ms_mode = ms_mode && stereo;
+
+        let stages = &mut F::of(&mut self.stages)[..num_stages];
+        let last_output = &mut self.last_output;
+        let frozen = &mut self.frozen;
+        let self_osc = &mut self.self_osc;
+
+        // the dry signal too, it can reach the output through the mix, the
+        // bypass, or the monitor, whatever the wet path does
+        for sample in samples.iter_mut() {
+            *sample = sanitize(*sample);
+        }
+        if metering {
+            meters.input_peaks = meters.input_peaks.simd_max(peak(samples));
+        }
+        // the main output, before it's routed
+        let mut outputs = [Simd::splat(0.); CHUNK];
+
+        for (j, sample) in samples.iter_mut().enumerate() {
+            let input = *sample;
+
+            let gain = match &controls.coefficients {
+                Some(coefficients) => {
+                    let (mut w_c, gain) = coefficients[j];
+                    if vintage {
+                        // downwards only, so that it can't push the cutoff past Nyquist
+                        let dip = Simd::splat(VINTAGE_DEPTH) * soft_clip(input.abs());
+                        w_c *= Simd::splat(1.) - dip;
+                    }
+                    let (filter_gain, ..) = gain_position.split(mode, gain);
+                    // bypassed stages' smoothers stay put, see `StageParams::enabled`
+                    let active = stages.iter_mut().zip(active_stages).filter(|(_, on)| *on);
+                    for (f, _) in active {
+                        update(f, w_c, filter_gain, 1 << oversampling_log2);
+                    }
+                    gain
+                }
+                None => controls.gains.map_or(gain, |gains| gains[j]),
+            };
+            let (_, pre_gain, post_gain) = gain_position.split(mode, gain);
+
+            let (drive, fade, freeze) = (controls.drive[j], controls.fade[j], controls.freeze[j]);
+            let blend = Blend {
+                tilt: controls.tilt[j],
+                seesaw: controls.seesaw[j],
+            };
+            // see `OnePoleParams::get_values`
+            let feedback = Simd::splat(controls.feedback[j]);
+            let compensation = Simd::splat(1.) + feedback * dc_gain;
+
+            let mut dry = self.oversampler.delay_dry(oversampling_log2, input);
+
+            let mut x = input * pre_gain * controls.trim[j];
+
+            if denormal_mode == DenormalMode::Noise {
+                x += Simd::splat(self.noise.next(ANTI_DENORMAL));
+            }
+
+            self.rumble_filter.process(x);
+            if rumble {
+                x = self.rumble_filter.get_highpass();
+            }
 
-const NUM_CHANNELS: usize = 2;
+            x = warm(x, controls.warmth[j]);
 
-type Filter = OnePole<NUM_CHANNELS>;
+            self.dc_blocker.process(x);
+            if dc_block {
+                x = self.dc_blocker.get_highpass();
+            }
 
-#[derive(Params)]
-pub struct OnePoleParams {
-    #[id = "cutoff"]
-    cutoff: FloatParam,
-    #[id = "gain"]
-    gain: FloatParam,
-    #[id = "mode"]
-    mode: EnumParam<FilterMode>,
-}
+            if ms_mode {
+                x = Simd::splat(0.5) * ms_butterfly(x);
+            }
 
-impl Default for OnePoleParams {
-    fn default() -> Self {
-        Self {
-            cutoff: FloatParam::new("Cutoff", 0.5, FloatRange::Linear { min: 0., max: 1. })
-                .with_value_to_string(Arc::new(|value| {
-                    (MIN_FREQ * (MAX_FREQ / MIN_FREQ).powf(value)).to_string()
-                })),
+            let mut wet = self.oversampler.process(oversampling_log2, x, |x| {
+                // the stages, and their smoothers, stay put
+                if freeze == 1. {
+                    return *frozen;
+                }
 
-            gain: FloatParam::new(
-                "Gain",
-                0.,
-                FloatRange::Linear {
-                    min: -30.,
-                    max: 30.,
-                },
-            )
-            .with_unit(" db"),
+                let mut wet =
+                    compensation * saturate(x, drive) - feedback * soft_clip(*last_output);
+                if let Some(osc) = &controls.self_osc {
+                    let (w, level) = osc[j];
+                    wet += Simd::splat(level) * self_osc.next(w);
+                }
+                let active = stages.iter_mut().zip(active_stages).filter(|(_, on)| *on);
+                for (f, _) in active {
+                    f.update_smoothers();
+                    f.process(wet);
+                    wet = output.get(f, blend);
+                }
+
+                // only the last stage's output is crossfaded, the earlier
+                // stages switch to the new mode immediately
+                *last_output = if fade > 0. {
+                    let prev = prev_output.get(&stages[last_stage], blend);
+                    wet + Simd::splat(fade) * (prev - wet)
+                } else {
+                    wet
+                };
+
+                // crossfading in, or out, of the held output
+                if freeze > 0. {
+                    *last_output + Simd::splat(freeze) * (*frozen - *last_output)
+                } else {
+                    *frozen = *last_output;
+                    *last_output
+                }
+            }) * post_gain;
+
+            // at the host's rate, like the DC blocker
+            self.damper.update_smoothers();
+            self.damper.process(wet);
+            if damp {
+                wet = self.damper.get_lowpass();
+            }
+
+            let width = if stereo { controls.width[j] } else { 1. };
+            if ms_mode || width != 1. {
+                if !ms_mode {
+                    wet = Simd::splat(0.5) * ms_butterfly(wet);
+                }
+                wet = ms_butterfly(wet * Simd::from_array([1., width]));
+            }
+            let mut filtered = wet;
+            wet *= makeup;
+            if headroom {
+                wet *= Simd::splat(resonance_headroom(controls.feedback[j]));
+            }
+
+            if ceiling_mode == Ceiling::TruePeak {
+                [dry, filtered] = self.limiter.align([dry, filtered]);
+                wet = self.limiter.process(wet, ceiling, true_peak_release);
+                meters.ceiling_hit |= self.limiter.limiting();
+            } else {
+                let limited = ceiling_mode.limit(wet, ceiling);
+                meters.ceiling_hit |= limited.simd_ne(wet).any();
+                wet = limited;
+            }
+
+            wet = monitor.wet(dry, filtered, wet) * Simd::splat(controls.reset_fade[j]);
+            // after mid/side decoding, so that both the left and right channels flip
+            if invert {
+                wet = -wet;
+            }
+            if stereo {
+                wet = self.haas.process(wet, controls.haas[j]);
+            }
+
+            let out = mix_dry_wet(dry, wet, controls.mix[j]);
+            outputs[j] = out;
+
+            if crossover {
+                (aux.low[j], aux.high[j]) = self.crossover.split(out);
+            }
+
+            (*sample, aux.send[j]) = routing.route(dry, out);
+            // last, anything after it would undo it
+            *sample = self.ditherer.process(dither, *sample);
+        }
 
-            mode: EnumParam::new("Filter Mode", FilterMode::default()),
+        if metering {
+            let outputs = &mut outputs[..samples.len()];
+            // a lone last channel's empty lane, whatever the filters left in it
+            if lanes < LANES {
+                for out in outputs.iter_mut() {
+                    out[1] = 0.;
+                }
+            }
+            meters.output_peaks = meters.output_peaks.simd_max(peak(outputs));
+            meters.output_energy += energy(outputs);
         }
     }
 }
 
-impl OnePoleParams {
-    fn get_values(&self, pi_tick: f32) -> (f32x2, f32x2, FilterMode) {
-        let cutoff_normalized = self.cutoff.unmodulated_plain_value();
-        let gain_normalized = self.gain.unmodulated_plain_value();
-        (
-            Simd::splat(pi_tick * MIN_FREQ * (MAX_FREQ / MIN_FREQ).powf(cutoff_normalized)),
-            Simd::splat(10f32.powf(gain_normalized * (1. / 20.))),
-            self.mode.unmodulated_plain_value(),
-        )
-    }
+/// What a channel's filters were last updated with, see [`OnePoleFilter::coefficients`]
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct Coefficients {
+    /// angular cutoff, in radians per sample, at the oversampled rate, before
+    /// the bilinear transform's prewarping
+    pub w_c: f32,
+    /// the filters' own, linear, gain, only the shelves use it
+    pub gain: f32,
 }
 
-#[derive(Default)]
 pub struct OnePoleFilter {
     params: Arc<OnePoleParams>,
+    sample_rate: f32,
+    /// `TAU / sample_rate`, at the oversampled rate
     pi_tick: f32,
-    filter: Filter,
+    pairs: [ChannelPair; NUM_FILTERS],
+    mode: Mode,
+    /// mode we're fading away from
+    prev_mode: Mode,
+    /// weight of `prev_mode`'s output, ramps from 1 down to 0 after a mode change
+    mode_fade: f32,
+    /// weight of the dry signal, ramps towards 1 when bypassed, 0 otherwise
+    bypass_fade: f32,
+    /// weight of the held output, ramps towards 1 when frozen, 0 otherwise
+    freeze_fade: f32,
+    /// per sample increment of crossfades, so that they last `FADE_MS`
+    fade_step: f32,
+    /// gain of the wet signal, see [`Self::soft_reset`]
+    reset_fade: f32,
+    /// whether a soft reset is fading the wet signal out, the filters
+    /// are reset once it's silent
+    reset_pending: bool,
+    /// whether the transport was playing during the last processed block
+    playing: bool,
+    /// follows the input's level, when `env_depth` isn't 0
+    envelope: Envelope,
+    /// modulates the cutoff, when `fm_depth` isn't 0
+    fm: Fm,
+    /// what the coefficients were last updated for, `None` if they might have
+    /// moved since (e. g. when updated every sample)
+    targets: Option<Targets>,
+    /// angular cutoffs and filter gains of both lanes, as of the last update
+    coefficients: (f32x2, f32x2),
+    /// most recently pressed note, for key tracking
+    last_note: Option<u8>,
+    /// glides towards `last_note`
+    glide: Glide,
+    /// slowly moves the cutoff around, when `drift` isn't 0
+    drift: Drift,
+    /// most recent brightness expression or `EXPRESSION_CC` value
+    expression: Option<f32>,
+    num_stages: usize,
+    oversampling_log2: usize,
+    character: Character,
+    /// last latency reported to the host
+    latency: u32,
+    meters: Arc<Meters>,
+    /// per sample decay factor of the peak meter
+    peak_decay: f32,
+    /// see [`TruePeakLimiter::process`]
+    true_peak_release: f32,
+    /// whether the true peak limiter was on during the last processed block
+    true_peak: bool,
+    /// whether the last processed block had a front left and right pair, the only
+    /// one delayed by the Haas effect
+    stereo: bool,
+    /// all of `params`, see [`Self::snap_smoothers`]
+    smoothed_params: Vec<ParamPtr>,
+    /// see [`Self::set_deterministic`]
+    deterministic: bool,
+    /// the morph's value, as of the last processed block, `None` before the first one
+    last_morph: Option<f32>,
+}
+
+impl Default for OnePoleFilter {
+    fn default() -> Self {
+        let mut plugin = Self {
+            params: Arc::default(),
+            sample_rate: DEFAULT_SAMPLE_RATE,
+            pi_tick: Default::default(),
+            pairs: array::from_fn(ChannelPair::new),
+            mode: Default::default(),
+            prev_mode: Default::default(),
+            mode_fade: Default::default(),
+            bypass_fade: Default::default(),
+            freeze_fade: Default::default(),
+            fade_step: Default::default(),
+            reset_fade: Default::default(),
+            reset_pending: Default::default(),
+            playing: Default::default(),
+            envelope: Default::default(),
+            fm: Default::default(),
+            targets: Default::default(),
+            coefficients: Default::default(),
+            last_note: Default::default(),
+            glide: Default::default(),
+            drift: Default::default(),
+            expression: Default::default(),
+            num_stages: Default::default(),
+            oversampling_log2: Default::default(),
+            character: Default::default(),
+            latency: Default::default(),
+            meters: Default::default(),
+            peak_decay: Default::default(),
+            true_peak_release: Default::default(),
+            true_peak: Default::default(),
+            stereo: Default::default(),
+            smoothed_params: Default::default(),
+            deterministic: Default::default(),
+            last_morph: Default::default(),
+        };
+        // some hosts process before initializing, during scans, everything that
+        // depends on the sample rate has to be usable by then
+        plugin.setup(DEFAULT_SAMPLE_RATE);
+        plugin
+    }
+}
+
+impl OnePoleFilter {
+    /// Current latency, in samples. Anything adding latency must be accounted for here
+    fn latency(&self) -> u32 {
+        let limiter = if self.params.ceiling_mode.value() == Ceiling::TruePeak {
+            true_peak::LATENCY
+        } else {
+            0
+        };
+        (oversampling::latency(self.oversampling_log2) + limiter) as u32
+    }
+
+    /// Reports the current latency to the host, if it has changed since
+    /// the last time it was reported
+    fn update_latency(&mut self, set_latency_samples: impl FnOnce(u32)) {
+        let latency = self.latency();
+        if latency != self.latency {
+            self.latency = latency;
+            set_latency_samples(latency);
+        }
+    }
+
+    /// Moves the smoothers of the parameters the morph drives to where it's at, and
+    /// the mode along, when it's moved. On the first block, they're moved regardless,
+    /// the knobs having been left where they were while it was driving them
+    fn follow_morph(&mut self) {
+        let t = self.params.morph.value();
+        if self.last_morph == Some(t) {
+            return;
+        }
+        self.last_morph = Some(t);
+
+        let params = &self.params;
+        let Some(point) = params.morph_points.at(t) else {
+            return;
+        };
+        for cutoff in [&params.cutoff_l, &params.cutoff_r] {
+            cutoff.smoothed.set_target(self.sample_rate, point.cutoff);
+        }
+        params
+            .gain
+            .smoothed
+            .set_target(self.sample_rate, point.gain);
+        params.mix.smoothed.set_target(self.sample_rate, point.mix);
+        params
+            .morph_points
+            .set_mode(point.mode, params.mode.value());
+    }
+
+    /// Clears the filters' state at once
+    fn hard_reset(&mut self) {
+        for p in &mut self.pairs {
+            p.reset();
+        }
+        self.envelope.reset();
+        self.fm.reset();
+        // the drift restarts either way, so that bounces match, see `Drift`
+        if self.deterministic {
+            self.reseed();
+        } else {
+            self.drift.reset();
+        }
+        self.reset_pending = false;
+    }
+
+    /// Restarts every random source from its seed: the drift, the denormal noise,
+    /// and the dither
+    fn reseed(&mut self) {
+        self.drift.reset();
+        for p in &mut self.pairs {
+            p.noise.reset();
+            p.ditherer.reseed();
+        }
+    }
+
+    /// Jumps the parameters' smoothers, and the coefficients', straight to where they're
+    /// heading, so that the filters don't start off sweeping from stale values
+    fn snap_smoothers(&mut self) {
+        for param in &self.smoothed_params {
+            // SAFETY: the smoothers are only ever touched by the audio thread, this is it,
+            // or it isn't running yet
+            unsafe { param.update_smoother(self.sample_rate, true) };
+        }
+        self.snap_coefficients::<Filter>();
+        self.snap_coefficients::<MatchedOnePole>();
+        self.snap_coefficients::<DoubleOnePole>();
+    }
+
+    /// Clears the filters' state without clicking: the wet signal is faded out,
+    /// over `FADE_MS`, the filters are reset once it's silent, then it's faded back in
+    fn soft_reset(&mut self) {
+        self.reset_pending = true;
+    }
+
+    /// The modulation sources staying put for a whole block: note expressions,
+    /// and the drift. Key tracking can glide, so it's left out
+    fn block_modulation(&self) -> Modulation {
+        let mut modulation = Modulation::default();
+        modulation += self.params.expression_cents(self.expression);
+        modulation += self.drift.cents(self.params.drift.value());
+        modulation
+    }
+
+    /// Total factor applied to the cutoff frequency, from slope compensation,
+    /// and the modulation sources not moving within a block
+    fn freq_ratio(&self) -> f32 {
+        self.params.slope_ratio() * self.modulation_ratio()
+    }
+
+    /// Factor applied to the cutoff frequency by the modulation sources not moving
+    /// within a block, alone
+    fn modulation_ratio(&self) -> f32 {
+        let mut modulation = self.block_modulation();
+        modulation += self.params.key_cents(self.glide.note());
+        modulation.ratio()
+    }
+
+    fn set_oversampling(&mut self, factor_log2: usize) {
+        self.oversampling_log2 = factor_log2;
+        self.pi_tick = TAU / (self.sample_rate * (1 << factor_log2) as f32);
+    }
+
+    /// Everything `initialize` does, bar talking to the host
+    fn setup(&mut self, sample_rate: f32) {
+        // the state may just have been restored
+        self.params.sanitize();
+        // collected here, not to allocate on the audio thread
+        self.smoothed_params = self
+            .params
+            .param_map()
+            .into_iter()
+            .map(|(_, param, _)| param)
+            .collect();
+        for p in &mut self.pairs {
+            p.haas.allocate(sample_rate);
+        }
+
+        self.oversampling_log2 = self.params.oversampling_log2();
+        self.set_sample_rate(sample_rate);
+        self.snap_smoothers();
+        self.latency = self.latency();
+
+        self.mode = self.params.current_mode();
+        self.last_morph = None;
+        self.mode_fade = 0.;
+        self.reset_fade = 1.;
+        self.reset_pending = false;
+        self.character = self.params.character.value();
+        self.bypass_fade = if self.params.bypass.value() { 1. } else { 0. };
+        self.freeze_fade = if self.params.freeze.value() { 1. } else { 0. };
+        self.num_stages = self.params.num_stages();
+        self.meters.response.publish(self.operating());
+    }
+
+    /// Gets the plugin ready to process audio at `sample_rate`, outside of a plugin
+    /// host (e. g. in benchmarks), standing in for the host calling `initialize`.
+    /// Parameters can be changed beforehand, through `Plugin::params`
+    pub fn prepare(&mut self, sample_rate: f32) {
+        self.setup(sample_rate);
+        if self.deterministic {
+            self.reseed();
+        }
+    }
+
+    fn resonance(&self) -> f32 {
+        self.params
+            .modulated_resonance(self.params.resonance.value())
+    }
+
+    /// Time constant, in samples, of the filters' decay, at the lower of the cutoffs,
+    /// where it's the slowest. That of a one pole filter, `1 / (TAU * cutoff)`, or, with
+    /// resonance, that of a resonator with a Q of `1 / (1 - k)`, `k` being the loop's
+    /// feedback amount, `Q / (PI * cutoff)`
+    fn time_constant(&self) -> f32 {
+        let resonance = self.resonance();
+        let [l, r] = self.params.cutoffs();
+        let cutoff = l.min(r) * self.freq_ratio();
+
+        let time_constant = if resonance == 0. {
+            1. / (TAU * cutoff)
+        } else {
+            let q = 1. / (1. - resonance * MAX_FEEDBACK);
+            q / (PI * cutoff)
+        };
+        time_constant * self.sample_rate
+    }
+
+    /// Samples it takes the filters' output, now at `level`, to decay `TAIL_DECAY_DB`
+    /// below full scale
+    fn decay_length(&self, level: f32) -> u32 {
+        // `level * e^(-t / time_constant)` reaches `-TAIL_DECAY_DB`
+        let decay = (level.ln() + TAIL_DECAY_DB * LN_10 / 20.).max(0.);
+        (self.time_constant() * decay).ceil() as u32
+    }
+
+    /// The Haas delay, in whole samples, see [`OnePoleParams::haas`], 0 without
+    /// a front pair to delay
+    fn haas_delay(&self) -> u32 {
+        if !self.stereo {
+            return 0;
+        }
+        (self.params.haas.value() / 1000. * self.sample_rate).ceil() as u32
+    }
+
+    /// Length, in samples, of the tail of a full scale signal, in the worst case.
+    /// Without resonance, the filters die out about as fast as any, and there's none.
+    /// With it, the tail lasts until the resonance's ringing is `TAIL_DECAY_DB` down
+    pub fn tail(&self) -> u32 {
+        if self.resonance() == 0. {
+            0
+        } else {
+            self.decay_length(1.)
+        }
+    }
+
+    /// Samples left, after a silent block, whose output's peak was `output_peak`, until
+    /// the output is silent too, counting the latency, and the Haas delay. `None` while
+    /// the parameters shaping the tail are still gliding, it can't be told yet, or while
+    /// self-oscillating, or frozen
+    pub fn remaining_tail(&self, output_peak: f32) -> Option<u32> {
+        // self-oscillating, or frozen, it never ends
+        let oscillating = self.params.self_osc.value() && SelfOsc::level(self.resonance()) > 0.;
+        let sustained = oscillating || self.params.freeze.value();
+        (!self.params.smoothing() && !sustained)
+            .then(|| self.latency + self.haas_delay() + self.decay_length(output_peak))
+    }
+
+    /// For test harnesses, comparing renders to golden files: when on, every random
+    /// source (the drift, the denormal noise, and the dither) restarts from its seed on
+    /// each reset, and each call to [`Self::prepare`], so that renders from those points
+    /// on are reproducible, bit for bit, whatever was processed before. Off by default,
+    /// the noise generators then simply keep running
+    pub fn set_deterministic(&mut self, deterministic: bool) {
+        self.deterministic = deterministic;
+    }
+
+    /// Processes interleaved stereo `samples` in place, the same way `process` does,
+    /// but outside of a plugin host, after calling [`Self::prepare`]. There is no
+    /// transport, so the LFO is off, and no MIDI events either
+    pub fn process_block(&mut self, samples: &mut [[f32; 2]]) {
+        self.render(
+            samples,
+            None::<&[[f32; 2]]>,
+            AuxOutputs::<[[f32; 2]]>::none(),
+            None,
+        );
+    }
+
+    /// [`Self::process_block`], with any number of `channels`, of the same length,
+    /// paired up the same way as the host's: the first two are the front left and
+    /// right channels, the others, and a lone channel, are only filtered
+    pub fn process_channels(&mut self, channels: &mut [&mut [f32]]) {
+        self.render(
+            channels,
+            None::<&[[f32; 2]]>,
+            AuxOutputs::<[[f32; 2]]>::none(),
+            None,
+        );
+    }
+
+    /// [`Self::process_block`], the host's sample rate now being `sample_rate`, as
+    /// reported by its transport. Like `process`, a new rate is followed right away,
+    /// without having to call [`Self::prepare`] again
+    pub fn process_block_at(&mut self, samples: &mut [[f32; 2]], sample_rate: f32) {
+        self.follow_sample_rate(sample_rate);
+        self.process_block(samples);
+    }
+
+    /// Switches to the host's `sample_rate`, if it's changed. Invalid rates are
+    /// ignored, the previous one is kept
+    fn follow_sample_rate(&mut self, sample_rate: f32) {
+        // others, before initializing it, don't provide one at all
+        let sample_rate = Some(sample_rate)
+            .filter(|&sample_rate| sample_rate > 0. && sample_rate.is_finite())
+            .unwrap_or(self.sample_rate);
+        if sample_rate != self.sample_rate {
+            self.set_sample_rate(sample_rate);
+        }
+    }
+
+    /// [`Self::process_block`], also writing the crossover's low and high bands
+    /// to `low` and `high`, as long as `samples`
+    pub fn process_block_with_bands(
+        &mut self,
+        samples: &mut [[f32; 2]],
+        low: &mut [[f32; 2]],
+        high: &mut [[f32; 2]],
+    ) {
+        let aux = AuxOutputs {
+            send: None,
+            bands: Some((low, high)),
+        };
+        self.render(samples, None::<&[[f32; 2]]>, aux, None);
+    }
+
+    /// Null test, for checking that settings meant to be transparent (bypass, a mix
+    /// of 0...) really are. Processes `samples` like [`Self::process_block`], then
+    /// replaces them with the difference between the output and the input, delayed
+    /// by the plugin's latency, and scaled, in each channel, by the gain best matching
+    /// it to the output, which is returned. What's left is all the plugin adds, that's
+    /// more than a change of level.
+    ///
+    /// The whole signal is expected at once, right after [`Self::prepare`]
+    pub fn null_test(&mut self, samples: &mut [[f32; 2]]) -> [f32; 2] {
+        let input = samples.to_vec();
+        self.process_block(samples);
+
+        let latency = (self.latency() as usize).min(input.len());
+        let delayed = || iter::repeat_n([0.; 2], latency).chain(input.iter().copied());
+
+        let gains = [0, 1].map(|lane| {
+            let (mut correlation, mut energy) = (0., 0.);
+            for (x, y) in delayed().zip(samples.iter()) {
+                correlation += x[lane] * y[lane];
+                energy += x[lane] * x[lane];
+            }
+            if energy > 0. {
+                correlation / energy
+            } else {
+                1.
+            }
+        });
+
+        for (x, y) in delayed().zip(samples.iter_mut()) {
+            for lane in 0..2 {
+                y[lane] -= gains[lane] * x[lane];
+            }
+        }
+        gains
+    }
+
+    /// The coefficients the filters of the left and right channels were last
+    /// updated with. With more than two channels, even channels share the left's,
+    /// odd ones, the right's. With the vintage option on, the dip caused by the
+    /// signal's level is left out
+    pub fn coefficients(&self) -> [Coefficients; 2] {
+        let (w_c, gain) = self.coefficients;
+        [0, 1].map(|lane| Coefficients {
+            w_c: w_c[lane],
+            gain: gain[lane],
+        })
+    }
+
+    /// The operating state, besides the parameters, the response is computed at
+    fn operating(&self) -> Operating {
+        Operating {
+            sample_rate: self.sample_rate,
+            pi_tick: self.pi_tick,
+            freq_ratio: self.freq_ratio(),
+        }
+    }
+
+    /// Magnitude of the plugin's response, as linear gains, at each of `freqs`, in Hz,
+    /// to the same signal in both channels (that of the left one, if they differ).
+    /// It's computed from the current settings, without processing any audio, after
+    /// [`Self::prepare`], or the host's initialization. The nonlinear parts (the drive,
+    /// the resonance loop's clipper and the ceiling) and the oversampling filters
+    /// are left out
+    pub fn magnitude_response(&self, freqs: &[f32]) -> Vec<f32> {
+        let response = self.params.response(self.operating(), freqs);
+        response.into_iter().map(|h| h.norm() as f32).collect()
+    }
+
+    /// The response, as in [`Self::magnitude_response`], at `num_freqs` frequencies,
+    /// log-spaced over the cutoff's range, or up to Nyquist, if it's lower, with
+    /// the phase too, if `with_phase` is set
+    pub fn response_curve(&self, num_freqs: usize, with_phase: bool) -> ResponseCurve {
+        self.params
+            .response_curve(self.operating(), num_freqs, with_phase)
+    }
+
+    /// Processes `audio`, `lfo` being the LFO's state at its start, if it's on.
+    /// The envelope follower follows `sidechain`, if there's one, `audio` otherwise.
+    /// The auxiliary outputs have as many channels and samples as `audio`
+    fn render<A, S, O>(
+        &mut self,
+        audio: &mut A,
+        sidechain: Option<&S>,
+        aux: AuxOutputs<O>,
+        lfo: Option<Lfo>,
+    ) where
+        A: PairedChannels + ?Sized,
+        S: PairedChannels + ?Sized,
+        O: PairedChannels + ?Sized,
+    {
+        let _ftz = (self.params.denormal_mode.value() == DenormalMode::Ftz).then(ScopedFtz::enable);
+
+        self.follow_morph();
+
+        let oversampling_log2 = self.params.oversampling_log2();
+        if oversampling_log2 != self.oversampling_log2 {
+            self.set_oversampling(oversampling_log2);
+            for p in &mut self.pairs {
+                p.reset();
+            }
+        }
+
+        // it's bypassed otherwise, and what's left in it is stale
+        let true_peak = self.params.ceiling_mode.value() == Ceiling::TruePeak;
+        if true_peak && !self.true_peak {
+            for p in &mut self.pairs {
+                p.limiter.reset();
+            }
+        }
+        self.true_peak = true_peak;
+
+        let character = self.params.character.value();
+        if character != self.character {
+            self.character = character;
+            match character {
+                Character::Clean => self.wake_stages::<Filter>(),
+                Character::Analog => self.wake_stages::<MatchedOnePole>(),
+                Character::Precise => self.wake_stages::<DoubleOnePole>(),
+            }
+        }
+
+        match character {
+            Character::Clean => self.render_with::<Filter, _, _, _>(audio, sidechain, aux, lfo),
+            Character::Analog => {
+                self.render_with::<MatchedOnePole, _, _, _>(audio, sidechain, aux, lfo)
+            }
+            Character::Precise => {
+                self.render_with::<DoubleOnePole, _, _, _>(audio, sidechain, aux, lfo)
+            }
+        }
+    }
+
+    /// Clears the state of idle stages of type `F`, and brings their
+    /// coefficients up to date, before they're put to use
+    fn wake_stages<F: Stage>(&mut self) {
+        for p in &mut self.pairs {
+            stage::reset(F::of(&mut p.stages));
+        }
+        self.snap_coefficients::<F>();
+    }
+
+    /// [`Self::render`], with stages of type `F`
+    fn render_with<F, A, S, O>(
+        &mut self,
+        audio: &mut A,
+        sidechain: Option<&S>,
+        mut aux: AuxOutputs<O>,
+        mut lfo: Option<Lfo>,
+    ) where
+        F: Stage,
+        A: PairedChannels + ?Sized,
+        S: PairedChannels + ?Sized,
+        O: PairedChannels + ?Sized,
+    {
+        let oversampling_log2 = self.oversampling_log2;
+        let oversampling = 1 << oversampling_log2;
+
+        self.glide
+            .set_time(self.params.glide.value(), self.sample_rate);
+
+        let num_samples = audio.num_samples();
+        self.drift.advance(num_samples as u32);
+
+        let freq_ratio = self.freq_ratio();
+        let Values {
+            w_c,
+            gain,
+            dc_gain,
+            makeup,
+            mode,
+            ..
+        } = self.params.get_values(self.pi_tick, freq_ratio);
+
+        if mode != self.mode {
+            self.prev_mode = self.mode;
+            self.mode = mode;
+            self.mode_fade = 1.;
+        }
+
+        // split for the new mode, the previous one's output fades out with it
+        let gain_position = self.params.gain_position.value();
+        let (filter_gain, ..) = gain_position.split(mode, gain);
+
+        let update = F::smoothing_update_function(mode.filter_mode());
+        let output = mode.output();
+        let prev_output = self.prev_mode.output();
+
+        let num_filters = audio.num_pairs();
+        self.stereo = audio.num_channels() >= LANES;
+        let pairs = &mut self.pairs[..num_filters];
+
+        let num_stages = self.params.num_stages();
+        if num_stages > self.num_stages {
+            // don't let stages that were just added ring with stale state. Bypassed
+            // ones, on the other hand, pick up where they left off
+            for p in pairs.iter_mut() {
+                stage::reset(&mut F::of(&mut p.stages)[self.num_stages..num_stages]);
+            }
+        }
+        self.num_stages = num_stages;
+
+        let active_stages = self.params.active_stages();
+        let last_stage = (0..MAX_STAGES)
+            .rev()
+            .find(|&i| active_stages[i])
+            .unwrap_or(0);
+
+        let link = self.params.link.value();
+        let cutoff_l = &self.params.cutoff_l.smoothed;
+        let cutoff_r = &self.params.cutoff_r.smoothed;
+        let pivot = &self.params.pivot.smoothed;
+        let pivot_override = self.params.pivot_override();
+        let gain_db = &self.params.gain.smoothed;
+
+        let env_depth = self.params.env_depth.value();
+        let follow_envelope = env_depth != 0.;
+        if follow_envelope {
+            self.envelope.set(
+                self.params.env_attack.value(),
+                self.params.env_release.value(),
+                env_depth,
+                self.sample_rate,
+            );
+        } else {
+            self.envelope.reset();
+        }
+
+        let vintage = self.params.vintage.value();
+
+        let damping_freq = self.params.damping_freq();
+        if let Some(freq) = damping_freq {
+            let w_c = Simd::splat(TAU * freq / self.sample_rate);
+            let update = Filter::get_smoothing_update_function(FilterMode::LP);
+            for p in pairs.iter_mut() {
+                update(&mut p.damper, w_c, Simd::splat(1.), num_samples);
+            }
+        }
+
+        // whether the cutoff is modulated by the LFO, the envelope, the FM oscillator,
+        // the input's level, or a glide
+        let modulated = lfo.is_some()
+            || follow_envelope
+            || self.params.fm_depth.value() > 0.
+            || vintage
+            || self.glide.is_gliding(self.last_note);
+
+        // when modulated, or while the cutoff is being automated, recompute the
+        // coefficients every sample, when only the gain is, every chunk, otherwise,
+        // they are updated once for the whole block. `quality` has the last word
+        let update_rate = self.params.quality.value().update_rate(
+            if modulated
+                || cutoff_l.is_smoothing()
+                || (!link && cutoff_r.is_smoothing())
+                || (pivot_override && pivot.is_smoothing())
+            {
+                UpdateRate::Sample
+            } else if gain_db.is_smoothing() {
+                UpdateRate::Chunk
+            } else {
+                UpdateRate::Block
+            },
+        );
+
+        let block = BlockSettings {
+            oversampling_log2,
+            num_stages,
+            active_stages,
+            last_stage,
+            update,
+            output,
+            prev_output,
+            gain,
+            gain_position,
+            mode,
+            dc_gain,
+            makeup,
+            headroom: self.params.resonance_headroom.value(),
+            ms_mode: self.params.ms_mode.value(),
+            dc_block: self.params.dc_blocked(),
+            rumble: self.params.rumble.value(),
+            damp: damping_freq.is_some(),
+            vintage,
+            monitor: self.params.monitor.value(),
+            invert: self.params.invert.value(),
+            routing: match aux.send {
+                Some(_) => self.params.routing.value(),
+                None => Routing::Main,
+            },
+            crossover: aux.bands.is_some() && self.params.crossover.value(),
+            ceiling_mode: self.params.ceiling_mode.value(),
+            ceiling: util::db_to_gain(self.params.ceiling.value()),
+            true_peak_release: self.true_peak_release,
+            denormal_mode: self.params.denormal_mode.value(),
+            dither: self.params.dither.value(),
+            // metering is not worth the trouble when no one's looking
+            metering: self.params.editor_state.is_open(),
+        };
+        let mut meters = BlockMeters::default();
+
+        let targets = Targets {
+            w_c,
+            gain: filter_gain,
+            mode,
+            character: self.character,
+            num_stages,
+            active_stages,
+            num_filters,
+        };
+
+        if update_rate != UpdateRate::Block {
+            self.targets = None;
+        } else if self.targets != Some(targets) {
+            // with static settings, the coefficients are already there
+            for p in pairs.iter_mut() {
+                let stages = F::of(&mut p.stages)[..num_stages].iter_mut();
+                for (f, _) in stages.zip(active_stages).filter(|(_, on)| *on) {
+                    update(f, w_c, filter_gain, num_samples * oversampling);
+                }
+            }
+            self.coefficients = (w_c, filter_gain);
+            self.targets = Some(targets);
+        }
+
+        // the last one is whatever's left
+        let chunk_size = self.params.chunk_size.value() as usize;
+        for start in (0..num_samples).step_by(chunk_size) {
+            let len = chunk_size.min(num_samples - start);
+
+            let levels = follow_envelope.then(|| match sidechain {
+                Some(sidechain) => peak_levels(sidechain, start, len),
+                None => peak_levels(audio, start, len),
+            });
+
+            let modulation = self.block_modulation();
+            let mut controls = self.next_controls(
+                len,
+                (modulated || update_rate == UpdateRate::Sample).then_some(modulation),
+                &mut lfo,
+                levels.as_ref(),
+            );
+
+            if update_rate == UpdateRate::Chunk {
+                let (w_c, gains) = match controls.coefficients.take() {
+                    // modulated, but updated every chunk, towards where the modulation
+                    // ends up, the cutoffs in between are left unused
+                    Some(coefficients) => (coefficients[len - 1].0, coefficients.map(|c| c.1)),
+                    None => self.chunk_coefficients(len, modulation),
+                };
+                let gain = gains[len - 1];
+                controls.gains = Some(gains);
+                let (filter_gain, ..) = gain_position.split(mode, gain);
+                for p in &mut self.pairs[..num_filters] {
+                    let stages = F::of(&mut p.stages)[..num_stages].iter_mut();
+                    for (f, _) in stages.zip(active_stages).filter(|(_, on)| *on) {
+                        update(f, w_c, filter_gain, len * oversampling);
+                    }
+                }
+                self.coefficients = (w_c, filter_gain);
+            }
+
+            if let Some(coefficients) = &controls.coefficients {
+                let (w_c, gain) = coefficients[len - 1];
+                self.coefficients = (w_c, gain_position.split(mode, gain).0);
+            }
+
+            if block.crossover {
+                let freq = self.params.crossover_freq.smoothed.next_step(len as u32);
+                for p in &mut self.pairs[..num_filters] {
+                    p.crossover.set(freq / self.sample_rate);
+                }
+            }
+
+            for (i, p) in self.pairs[..num_filters].iter_mut().enumerate() {
+                let layout = PairLayout {
+                    stereo: i == 0 && self.stereo,
+                    lanes: (audio.num_channels() - i * LANES).min(LANES),
+                };
+                let mut samples = [Simd::splat(0.); CHUNK];
+                let samples = &mut samples[..len];
+                let mut chunk = AuxChunk {
+                    send: [Simd::splat(0.); CHUNK],
+                    low: [Simd::splat(0.); CHUNK],
+                    high: [Simd::splat(0.); CHUNK],
+                };
+
+                audio.load(i, start, samples);
+                p.process_chunk(layout, &block, &controls, samples, &mut chunk, &mut meters);
+                audio.store(i, start, samples);
+                if let Some(send) = &mut aux.send {
+                    send.store(i, start, &chunk.send[..len]);
+                }
+                if let Some((low, high)) = &mut aux.bands {
+                    low.store(i, start, &chunk.low[..len]);
+                    high.store(i, start, &chunk.high[..len]);
+                }
+            }
+
+            if self.reset_pending && self.reset_fade == 0. {
+                self.hard_reset();
+            }
+        }
+
+        if meters.ceiling_hit {
+            self.meters.set_ceiling_hit();
+        }
+
+        if block.metering {
+            let decay = self.peak_decay.powi(num_samples as i32);
+            let published = &self.meters;
+            published.input.update(meters.input_peaks.to_array(), decay);
+            published
+                .output
+                .update(meters.output_peaks.to_array(), decay);
+            // the samples of each lane's channels, at least one, a mono lane's
+            // empty one stays at 0
+            let num_channels = audio.num_channels();
+            let lane_channels = [num_channels.div_ceil(LANES), num_channels / LANES];
+            let num_samples = lane_channels.map(|n| (num_samples * n.max(1)) as f64);
+            let output_rms = (meters.output_energy / Simd::from_array(num_samples)).sqrt();
+            published.set_output_rms(output_rms.cast().to_array());
+            published.response.publish(self.operating());
+        }
+    }
+
+    /// Advances the cutoff and gain smoothers by `len` samples, and returns the
+    /// angular cutoffs they end up at, and the linear gains of every sample, ramped
+    /// in dB, there. `modulation` is the same as in [`Self::next_controls`]
+    fn chunk_coefficients(
+        &self,
+        len: usize,
+        mut modulation: Modulation,
+    ) -> (f32x2, [f32x2; CHUNK]) {
+        let params = &self.params;
+        let steps = len as u32;
+
+        let cutoffs = params.map_cutoffs(
+            params.cutoff_l.smoothed.next_step(steps),
+            params.cutoff_r.smoothed.next_step(steps),
+            params.pivot.smoothed.next_step(steps),
+        );
+        modulation += params.key_cents(self.glide.note());
+        let freq_ratio = params.slope_ratio() * modulation.ratio();
+        let w_c = angular_cutoff(self.pi_tick, cutoffs, freq_ratio);
+
+        // smoothed in dB, a linear ramp would dip in loudness halfway through fades
+        let gain_limit = params.gain_mode.value();
+        let start_db = gain_limit.clamp(params.gain.smoothed.previous_value());
+        let end_db = gain_limit.clamp(params.gain.smoothed.next_step(steps));
+        let step_db = (end_db - start_db) / len as f32;
+
+        let mut gains = [Simd::splat(1.); CHUNK];
+        for (j, gain) in gains[..len].iter_mut().enumerate() {
+            *gain = linear_gain(start_db + step_db * (j + 1) as f32);
+        }
+        (w_c, gains)
+    }
+
+    /// Advances the smoothers and crossfades shared by all channel pairs by `len`
+    /// samples. The coefficients are computed every sample only if `modulation`,
+    /// the block's modulation, bar the sources moving every sample, is given. `levels` are the input's
+    /// peak levels, for the envelope follower, if it's on
+    fn next_controls(
+        &mut self,
+        len: usize,
+        modulation: Option<Modulation>,
+        lfo: &mut Option<Lfo>,
+        levels: Option<&[f32; CHUNK]>,
+    ) -> Controls {
+        // at the cutoff, without the slope compensation
+        let self_osc = self.params.self_osc.value();
+        let mut self_osc_w = [Simd::splat(0.); CHUNK];
+        let block_ratio = self.modulation_ratio();
+
+        let params = &self.params;
+        let envelope = &mut self.envelope;
+
+        let (glide, last_note) = (&mut self.glide, self.last_note);
+        let gain_limit = params.gain_mode.value();
+
+        let (fm, sample_rate) = (&mut self.fm, self.sample_rate);
+        let (fm_ratio, fm_depth) = (params.fm_ratio.value(), params.fm_depth.value());
+
+        let slope_ratio = params.slope_ratio();
+
+        let coefficients = modulation.map(|block_modulation| {
+            let mut coefficients = [(Simd::splat(0.), Simd::splat(0.)); CHUNK];
+            for (j, c) in coefficients[..len].iter_mut().enumerate() {
+                let cutoffs = params.map_cutoffs(
+                    params.cutoff_l.smoothed.next(),
+                    params.cutoff_r.smoothed.next(),
+                    params.pivot.smoothed.next(),
+                );
+                let mut modulation = block_modulation;
+                if let Some(lfo) = lfo.as_mut() {
+                    modulation += lfo.next_cents();
+                }
+                if let Some(levels) = levels {
+                    modulation += envelope.next_cents(levels[j]);
+                }
+                let note = glide.next(last_note);
+                modulation += params.key_cents(note);
+                // follows the played note, even without key tracking
+                if fm_depth > 0. {
+                    let note = note.unwrap_or(KEY_TRACK_CENTER.into());
+                    modulation += fm.next_cents(note, fm_ratio, fm_depth, sample_rate);
+                }
+                let freq_ratio = slope_ratio * modulation.ratio();
+                let w_c = angular_cutoff(self.pi_tick, cutoffs, freq_ratio);
+                let gain_db = gain_limit.clamp(params.gain.smoothed.next());
+                *c = (w_c, linear_gain(gain_db));
+                if self_osc {
+                    self_osc_w[j] = angular_cutoff(self.pi_tick, cutoffs, modulation.ratio());
+                }
+            }
+            coefficients
+        });
+        if self_osc && coefficients.is_none() {
+            // the cutoff isn't read every sample then, it's at most a chunk behind
+            self_osc_w = [angular_cutoff(self.pi_tick, params.cutoffs(), block_ratio); CHUNK];
+        }
+
+        let mut controls = Controls {
+            mix: [(0., 0.); CHUNK],
+            drive: [0.; CHUNK],
+            warmth: [0.; CHUNK],
+            tilt: [0.; CHUNK],
+            seesaw: [1.; CHUNK],
+            feedback: [0.; CHUNK],
+            width: [0.; CHUNK],
+            haas: [0.; CHUNK],
+            trim: [Simd::splat(1.); CHUNK],
+            reset_fade: [0.; CHUNK],
+            fade: [0.; CHUNK],
+            freeze: [0.; CHUNK],
+            coefficients,
+            gains: None,
+            self_osc: self_osc.then_some([(Simd::splat(0.), 0.); CHUNK]),
+        };
+
+        // the filters keep running while bypassed, so that they're warmed
+        // up by the time the bypass is released
+        let bypass = self.params.bypass.value();
+        let freeze = self.params.freeze.value();
+        let monitoring = self.params.monitor.value() != Monitor::Normal;
+        let mix_law = self.params.mix_law.value();
+        let num_stages = self.params.num_active_stages();
+
+        for j in 0..len {
+            controls.drive[j] = self.params.drive.smoothed.next();
+            controls.warmth[j] = self.params.warmth.smoothed.next();
+            controls.tilt[j] = self.params.tilt.smoothed.next();
+            let slope_db = self.params.slope_db.smoothed.next();
+            controls.seesaw[j] = seesaw_gain(slope_db, num_stages);
+            let resonance = self.params.resonance.smoothed.next();
+            let resonance = self.params.modulated_resonance(resonance);
+            controls.feedback[j] = resonance * MAX_FEEDBACK;
+            if let Some(osc) = &mut controls.self_osc {
+                osc[j] = (self_osc_w[j], SelfOsc::level(resonance));
+            }
+            controls.width[j] = self.params.width.smoothed.next();
+            controls.haas[j] = self.params.haas.smoothed.next() / 1000. * self.sample_rate;
+            let trim = [&self.params.trim_l, &self.params.trim_r];
+            controls.trim[j] = Simd::from_array(trim.map(|t| util::db_to_gain(t.smoothed.next())));
+
+            controls.fade[j] = self.mode_fade;
+            self.mode_fade = (self.mode_fade - self.fade_step).max(0.);
+
+            controls.reset_fade[j] = self.reset_fade;
+            self.reset_fade = if self.reset_pending {
+                (self.reset_fade - self.fade_step).max(0.)
+            } else {
+                (self.reset_fade + self.fade_step).min(1.)
+            };
+
+            self.bypass_fade = if bypass {
+                (self.bypass_fade + self.fade_step).min(1.)
+            } else {
+                (self.bypass_fade - self.fade_step).max(0.)
+            };
+
+            controls.freeze[j] = self.freeze_fade;
+            self.freeze_fade = if freeze {
+                (self.freeze_fade + self.fade_step).min(1.)
+            } else {
+                (self.freeze_fade - self.fade_step).max(0.)
+            };
+            // a fully bypassed plugin outputs the dry signal untouched
+            let mix = self.params.mix.smoothed.next();
+            let mix = if monitoring { 1. } else { mix };
+            controls.mix[j] = mix_law.gains(mix * (1. - self.bypass_fade));
+        }
+
+        controls
+    }
+
+    /// Recomputes everything depending on the sample rate. The filters' coefficients
+    /// jump straight to their new values, as the old ones are meaningless at that rate
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.set_oversampling(self.oversampling_log2);
+
+        self.fade_step = 1000. / (FADE_MS * sample_rate);
+        self.drift.set_sample_rate(sample_rate);
+        // -12 dB is a factor of 0.25
+        self.peak_decay = 0.25f32.powf(1000. / (PEAK_DECAY_MS * sample_rate));
+        self.true_peak_release = (-1000. / (TRUE_PEAK_RELEASE_MS * sample_rate)).exp();
+
+        self.snap_coefficients::<Filter>();
+        self.snap_coefficients::<MatchedOnePole>();
+        self.snap_coefficients::<DoubleOnePole>();
+
+        // the DC blocker and rumble filter run at the host's rate, even when oversampling
+        let dc_block_w_c = Simd::splat(TAU * DC_BLOCK_FREQ / sample_rate);
+        let rumble_w_c = Simd::splat(TAU * RUMBLE_FREQ / sample_rate);
+        let update = Filter::get_update_function(FilterMode::HP);
+        for p in &mut self.pairs {
+            update(&mut p.dc_blocker, dc_block_w_c, Simd::splat(1.));
+            update(&mut p.rumble_filter, rumble_w_c, Simd::splat(1.));
+        }
+
+        let damping_freq = self.params.damping_freq().unwrap_or(MAX_FREQ);
+        let w_c = Simd::splat(TAU * damping_freq / sample_rate);
+        let update = Filter::get_update_function(FilterMode::LP);
+        for p in &mut self.pairs {
+            update(&mut p.damper, w_c, Simd::splat(1.));
+        }
+    }
+
+    /// Sets the coefficients of all stages of type `F` to their current targets, at once
+    fn snap_coefficients<F: Stage>(&mut self) {
+        let freq_ratio = self.freq_ratio();
+        let Values {
+            w_c, gain, mode, ..
+        } = self.params.get_values(self.pi_tick, freq_ratio);
+        let (filter_gain, ..) = self.params.gain_position.value().split(mode, gain);
+        let update = F::update_function(mode.filter_mode());
+
+        for p in &mut self.pairs {
+            for f in F::of(&mut p.stages) {
+                update(f, w_c, filter_gain);
+            }
+        }
+        self.coefficients = (w_c, filter_gain);
+        self.targets = None;
+    }
 }
 
 impl Plugin for OnePoleFilter {
@@ -80,7 +2848,7 @@ impl Plugin for OnePoleFilter {
 
     const VERSION: &'static str = "0.6.9";
 
-    const MIDI_INPUT: MidiConfig = MidiConfig::None;
+    const MIDI_INPUT: MidiConfig = MidiConfig::MidiCCs;
 
     const MIDI_OUTPUT: MidiConfig = MidiConfig::None;
 
@@ -88,15 +2856,22 @@ impl Plugin for OnePoleFilter {
 
     const HARD_REALTIME_ONLY: bool = false;
 
-    const AUDIO_IO_LAYOUTS: &'static [AudioIOLayout] = &[AudioIOLayout {
-        main_input_channels: NonZeroU32::new(NUM_CHANNELS as u32),
-        main_output_channels: NonZeroU32::new(NUM_CHANNELS as u32),
-        ..AudioIOLayout::const_default()
-    }];
+    // each layout comes with and without the sidechain, send and band outputs,
+    // for hosts unable to route them
+    const AUDIO_IO_LAYOUTS: &'static [AudioIOLayout] = &[
+        aux_layout("Stereo with Sidechain, Send and Bands", 2),
+        layout("Stereo", 2),
+        aux_layout("Mono with Sidechain, Send and Bands", 1),
+        layout("Mono", 1),
+        aux_layout("Quad with Sidechain, Send and Bands", 4),
+        layout("Quad", 4),
+        aux_layout("5.1 with Sidechain, Send and Bands", 6),
+        layout("5.1", 6),
+    ];
 
     type SysExMessage = ();
 
-    type BackgroundTask = ();
+    type BackgroundTask = PresetTask;
 
     fn params(&self) -> Arc<dyn Params> {
         self.params.clone()
@@ -105,60 +2880,132 @@ impl Plugin for OnePoleFilter {
     fn process(
         &mut self,
         buffer: &mut Buffer,
-        _aux: &mut AuxiliaryBuffers,
-        _context: &mut impl ProcessContext<Self>,
+        aux: &mut AuxiliaryBuffers,
+        context: &mut impl ProcessContext<Self>,
     ) -> ProcessStatus {
-        let (w_c, gain, mode) = self.params.get_values(self.pi_tick);
-        let update = Filter::get_smoothing_update_function(mode);
-        let get_output = Filter::get_output_function(mode);
+        // some hosts change the sample rate without re-initializing the plugin
+        let transport = context.transport();
+        self.playing = transport.playing;
+        self.follow_sample_rate(transport.sample_rate);
 
-        let f = &mut self.filter;
+        while let Some(event) = context.next_event() {
+            // this is a monophonic effect, with several notes held,
+            // the last one pressed, expressed, or modulated, wins
+            match event {
+                NoteEvent::NoteOn { note, .. } => self.last_note = Some(note),
+                NoteEvent::NoteOff {
+                    voice_id: Some(voice_id),
+                    ..
+                } => self.params.poly_mod.release(voice_id),
+                NoteEvent::PolyModulation {
+                    voice_id,
+                    poly_modulation_id,
+                    normalized_offset,
+                    ..
+                } => {
+                    self.params
+                        .poly_mod
+                        .modulate(poly_modulation_id, voice_id, normalized_offset);
+                }
+                // nih-plug has already set the parameter, and its smoother, to the new
+                // value, and there are no voices with smoothers of their own here
+                NoteEvent::MonoAutomation { .. } => (),
+                NoteEvent::PolyBrightness { brightness, .. } => self.expression = Some(brightness),
+                NoteEvent::MidiCC { cc, value, .. } => {
+                    if cc == EXPRESSION_CC {
+                        self.expression = Some(value);
+                    }
+                    if let Some(target) = self.params.midi_map.receive(cc) {
+                        self.params.apply_cc(target, value, self.sample_rate);
+                    }
+                }
+                _ => (),
+            }
+        }
 
-        let num_samples = buffer.samples();
-        update(f, w_c, gain, num_samples);
+        let lfo_depth = self.params.lfo_depth.value();
+        let lfo = (lfo_depth > 0.)
+            .then(|| {
+                let (rate, shape) = (self.params.lfo_rate.value(), self.params.lfo_shape.value());
+                Lfo::from_transport(context.transport(), rate, shape, lfo_depth)
+            })
+            .flatten();
 
-        for mut frame in buffer.iter_samples() {
-            // SAFETY: we only support a stereo configuration so these indices are valid
+        // without a sidechain, the envelope follows the main input
+        let sidechain = aux
+            .inputs
+            .first()
+            .map(Buffer::as_slice_immutable)
+            .filter(|channels| !channels.is_empty());
 
-            let mut sample = Simd::from_array(unsafe {
-                [*frame.get_unchecked_mut(0), *frame.get_unchecked_mut(1)]
-            });
+        // without a send output, everything goes to the main one, whatever the routing
+        let mut outputs = aux
+            .outputs
+            .iter_mut()
+            .map(|output| Some(output.as_slice()).filter(|channels| !channels.is_empty()));
+        let send = outputs.next().flatten();
+        let bands = outputs.next().flatten().zip(outputs.next().flatten());
+        let aux = AuxOutputs { send, bands };
 
-            f.update_smoothers();
-            f.process(sample);
+        // checked before it's processed in place
+        let silent = buffer
+            .as_slice_immutable()
+            .iter()
+            .all(|channel| channel.iter().all(|&sample| sample == 0.));
 
-            sample = get_output(f);
+        self.render(buffer.as_slice(), sidechain, aux, lfo);
+        self.update_latency(|latency| context.set_latency_samples(latency));
 
-            unsafe {
-                *frame.get_unchecked_mut(0) = sample[0];
-                *frame.get_unchecked_mut(1) = sample[1];
-            }
+        if !silent {
+            return ProcessStatus::Normal;
+        }
+
+        // the host may suspend the plugin once the tail is over
+        let output_peak = buffer
+            .as_slice_immutable()
+            .iter()
+            .flat_map(|channel| channel.iter())
+            .fold(0., |peak: f32, sample| peak.max(sample.abs()));
+        match self.remaining_tail(output_peak) {
+            Some(tail) => ProcessStatus::Tail(tail),
+            None => ProcessStatus::KeepAlive,
         }
+    }
 
-        ProcessStatus::Normal
+    fn task_executor(&mut self) -> TaskExecutor<Self> {
+        Box::new(bank::run)
     }
 
-    fn editor(&mut self, _async_executor: AsyncExecutor<Self>) -> Option<Box<dyn Editor>> {
-        None
+    fn editor(&mut self, async_executor: AsyncExecutor<Self>) -> Option<Box<dyn Editor>> {
+        // only the editor needs the user presets, `InitContext` can only run tasks
+        // synchronously, this loads them off the initializing thread
+        async_executor.execute_background(PresetTask::Load);
+        editor::create(self.params.clone(), self.meters.clone(), async_executor)
     }
 
     fn initialize(
         &mut self,
         _audio_io_layout: &AudioIOLayout,
         buffer_config: &BufferConfig,
-        _context: &mut impl InitContext<Self>,
+        context: &mut impl InitContext<Self>,
     ) -> bool {
-        self.pi_tick = TAU / buffer_config.sample_rate;
-
-        let (w_c, gain, mode) = self.params.get_values(self.pi_tick);
-        let update = Filter::get_update_function(mode);
+        self.setup(buffer_config.sample_rate);
 
-        update(&mut self.filter, w_c, gain);
+        // always report on initialization, the host may have forgotten about it
+        context.set_latency_samples(self.latency);
         true
     }
 
     fn reset(&mut self) {
-        self.filter.reset();
+        // some hosts reset plugins when looping, without stopping playback,
+        // the filters' state can only be cleared abruptly when nothing is playing
+        // the same goes for the smoothers, moving the dry signal's gain too
+        if self.playing {
+            self.soft_reset();
+        } else {
+            self.hard_reset();
+            self.snap_smoothers();
+        }
     }
 }
 
@@ -179,6 +3026,12 @@ impl ClapPlugin for OnePoleFilter {
     const CLAP_SUPPORT_URL: Option<&'static str> = None;
 
     const CLAP_FEATURES: &'static [ClapFeature] = &[ClapFeature::AudioEffect, ClapFeature::Filter];
+
+    // a single, global, voice, see [`PolyMod`]
+    const CLAP_POLY_MODULATION_CONFIG: Option<PolyModulationConfig> = Some(PolyModulationConfig {
+        max_voice_capacity: 1,
+        supports_overlapping_voices: true,
+    });
 }
 
 nih_export_clap!(OnePoleFilter);
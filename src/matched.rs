@@ -0,0 +1,149 @@
+use plugin_util::{filter::one_pole::FilterMode, simd::*};
+
+use core::f32::consts::PI;
+
+use crate::{
+    stage::{Stage, Stages},
+    MAX_STAGES,
+};
+
+/// Coefficient, gliding linearly towards its target
+#[derive(Default, Clone, Copy)]
+struct Smoothed {
+    value: f32x2,
+    step: f32x2,
+}
+
+/// One-pole filter modeled after the analog prototype more closely than the
+/// bilinear transform allows. The pole is placed by the impulse invariance method,
+/// and the zero, so that the lowpass response matches the analog one at both DC
+/// and Nyquist, where the bilinear transform's response falls to zero instead.
+/// Below a few kHz, both are virtually identical.
+///
+/// The lowpass output is `(b0 + b1 z^-1) / (1 - p z^-1)`, the others are derived
+/// from it, the same way as for the analog filter (e. g. `highpass = input - lowpass`),
+/// bar the allpass: with this lowpass' own zero, `2 lowpass - input` isn't flat, it's
+/// `(-p + z^-1) / (1 - p z^-1)` instead, around the same pole, computed on its own
+#[derive(Default)]
+pub struct MatchedOnePole {
+    p: Smoothed,
+    b0: Smoothed,
+    b1: Smoothed,
+    /// shelves' gain
+    gain: Smoothed,
+    /// number of samples left before the coefficients reach their targets
+    remaining: usize,
+    /// latest input
+    x: f32x2,
+    /// current lowpass output
+    y: f32x2,
+    /// current allpass output
+    ap: f32x2,
+}
+
+impl MatchedOnePole {
+    /// Coefficients `p`, `b0` and `b1`, for an angular cutoff of `w_c` radians per sample
+    pub fn coefficients(w_c: f32x2) -> [f32x2; 3] {
+        let one = Simd::splat(1.);
+        let p = (-w_c).exp();
+        // analog lowpass' magnitude at Nyquist
+        let ratio = Simd::splat(PI) / w_c;
+        let m = (one + ratio * ratio).sqrt().recip();
+
+        let half = Simd::splat(0.5);
+        let (a, b) = (one - p, m * (one + p));
+        [p, half * (a + b), half * (a - b)]
+    }
+
+    fn update(&mut self, w_c: f32x2, gain: f32x2) {
+        let [p, b0, b1] = Self::coefficients(w_c);
+        for (c, target) in [&mut self.p, &mut self.b0, &mut self.b1, &mut self.gain]
+            .into_iter()
+            .zip([p, b0, b1, gain])
+        {
+            *c = Smoothed {
+                value: target,
+                step: Simd::splat(0.),
+            };
+        }
+        self.remaining = 0;
+    }
+
+    fn update_smoothed(&mut self, w_c: f32x2, gain: f32x2, num_samples: usize) {
+        let [p, b0, b1] = Self::coefficients(w_c);
+        let n = Simd::splat(num_samples.max(1) as f32);
+        for (c, target) in [&mut self.p, &mut self.b0, &mut self.b1, &mut self.gain]
+            .into_iter()
+            .zip([p, b0, b1, gain])
+        {
+            c.step = (target - c.value) / n;
+        }
+        self.remaining = num_samples;
+    }
+
+    fn lowpass(&self) -> f32x2 {
+        self.y
+    }
+
+    fn highpass(&self) -> f32x2 {
+        self.x - self.y
+    }
+
+    fn allpass(&self) -> f32x2 {
+        self.ap
+    }
+
+    fn low_shelf(&self) -> f32x2 {
+        self.gain.value * self.lowpass() + self.highpass()
+    }
+
+    fn high_shelf(&self) -> f32x2 {
+        self.lowpass() + self.gain.value * self.highpass()
+    }
+}
+
+impl Stage for MatchedOnePole {
+    fn of(stages: &mut Stages) -> &mut [Self; MAX_STAGES] {
+        &mut stages.analog
+    }
+
+    fn update_function(_mode: FilterMode) -> fn(&mut Self, f32x2, f32x2) {
+        Self::update
+    }
+
+    fn smoothing_update_function(_mode: FilterMode) -> fn(&mut Self, f32x2, f32x2, usize) {
+        Self::update_smoothed
+    }
+
+    fn output_function(mode: FilterMode) -> fn(&Self) -> f32x2 {
+        match mode {
+            FilterMode::LP => Self::lowpass,
+            FilterMode::HP => Self::highpass,
+            FilterMode::AP => Self::allpass,
+            FilterMode::LSH => Self::low_shelf,
+            FilterMode::HSH => Self::high_shelf,
+        }
+    }
+
+    fn update_smoothers(&mut self) {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            for c in [&mut self.p, &mut self.b0, &mut self.b1, &mut self.gain] {
+                c.value += c.step;
+            }
+        }
+    }
+
+    fn process(&mut self, x: f32x2) {
+        let p = self.p.value;
+        self.y = self.b0.value * x + self.b1.value * self.x + p * self.y;
+        self.ap = self.x - p * x + p * self.ap;
+        self.x = x;
+    }
+
+    fn reset(&mut self) {
+        self.x = Simd::splat(0.);
+        self.y = Simd::splat(0.);
+        self.ap = Simd::splat(0.);
+    }
+}
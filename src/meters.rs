@@ -0,0 +1,71 @@
+use atomic_float::AtomicF32;
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::response::Snapshot;
+
+/// Peak absolute level of each lane, left then right, as linear gains, falling back
+/// down over time. With more than two channels, each lane gathers every other channel
+/// (see `PairedChannels`)
+#[derive(Default)]
+pub struct PeakMeter([AtomicF32; 2]);
+
+impl PeakMeter {
+    /// Peak levels of the left and right lanes
+    pub fn get(&self) -> [f32; 2] {
+        self.0.each_ref().map(|peak| peak.load(Ordering::Relaxed))
+    }
+
+    /// Raises each lane's peak level to that of `block_peaks` if it's louder, otherwise,
+    /// lets it fall by `decay`, the decay factor over the whole block
+    pub fn update(&self, block_peaks: [f32; 2], decay: f32) {
+        for (peak, block_peak) in self.0.iter().zip(block_peaks) {
+            let current = peak.load(Ordering::Relaxed);
+            let new = if block_peak > current {
+                block_peak
+            } else {
+                current * decay
+            };
+            peak.store(new, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Values published by the audio thread, for the editor to display.
+/// Everything in here is lock-free, so never blocks `process`
+#[derive(Default)]
+pub struct Meters {
+    /// of the plugin's input, before anything, the gain included, touches it
+    pub input: PeakMeter,
+    /// of the plugin's output
+    pub output: PeakMeter,
+    /// RMS level of each lane of the output, over the last block, as linear gains
+    output_rms: [AtomicF32; 2],
+    /// set whenever the ceiling brings down a sample, cleared by the editor
+    ceiling_hit: AtomicBool,
+    /// for the editor to draw the response curve
+    pub response: Snapshot,
+}
+
+impl Meters {
+    pub fn output_rms(&self) -> [f32; 2] {
+        self.output_rms
+            .each_ref()
+            .map(|rms| rms.load(Ordering::Relaxed))
+    }
+
+    pub fn set_output_rms(&self, rms: [f32; 2]) {
+        for (meter, rms) in self.output_rms.iter().zip(rms) {
+            meter.store(rms, Ordering::Relaxed);
+        }
+    }
+
+    pub fn set_ceiling_hit(&self) {
+        self.ceiling_hit.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the ceiling was hit since the last call
+    pub fn take_ceiling_hit(&self) -> bool {
+        self.ceiling_hit.swap(false, Ordering::Relaxed)
+    }
+}
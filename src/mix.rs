@@ -0,0 +1,31 @@
+use nih_plug::prelude::*;
+
+use core::f32::consts::FRAC_PI_2;
+
+/// Shape of the dry/wet crossfade
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MixLaw {
+    /// Gains adding up to 1, correlated signals (e. g. a gentle filter's output and
+    /// its input) keep their level, uncorrelated ones dip by 3 dB halfway through
+    #[default]
+    Linear,
+    /// Gains whose squares add up to 1, uncorrelated signals keep their level
+    #[name = "Equal Power"]
+    EqualPower,
+}
+
+impl MixLaw {
+    /// Gains of the dry and wet signals, for a `mix` in `0..=1`. Both laws
+    /// are exactly fully dry at 0, and fully wet at 1, so that they null
+    pub fn gains(self, mix: f32) -> (f32, f32) {
+        match (self, mix) {
+            (_, 0.) => (1., 0.),
+            (_, 1.) => (0., 1.),
+            (Self::Linear, mix) => (1. - mix, mix),
+            (Self::EqualPower, mix) => {
+                let (wet, dry) = (mix * FRAC_PI_2).sin_cos();
+                (dry, wet)
+            }
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use nih_plug::prelude::*;
+use plugin_util::{filter::one_pole::FilterMode, simd::*};
+
+use crate::stage::Stage;
+
+/// `FilterMode`'s modes, plus composite ones, blending several of the filter's taps
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    #[default]
+    Lowpass,
+    Highpass,
+    /// Flat magnitude response, only the phase is shifted, by up to 180° per stage,
+    /// half of it at the cutoff. With unlinked cutoffs, this decorrelates the channels
+    Allpass,
+    #[name = "Low Shelf"]
+    LowShelf,
+    #[name = "High Shelf"]
+    HighShelf,
+    /// Crossfade between the lowpass and highpass outputs, set by the tilt parameter
+    Tilt,
+    /// Seesaw around the cutoff, set by the tilt EQ's slope: the highs are turned up
+    /// as much as the lows are turned down, or the other way around, the cutoff
+    /// itself stays at 0 dB. See [`seesaw_gain`]
+    #[name = "Tilt EQ"]
+    TiltEq,
+}
+
+/// Per sample settings of the modes blending the lowpass and highpass outputs
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Blend {
+    /// the tilt parameter, see [`Mode::Tilt`]
+    pub tilt: f32,
+    /// each stage's gain of the highs, and inverse of that of the lows, see
+    /// [`Mode::TiltEq`]
+    pub seesaw: f32,
+}
+
+/// Each stage's [`Blend::seesaw`], for a cascade of `num_stages`, with highs
+/// `slope_db` decibels louder than the lows, altogether.
+///
+/// Blending a one pole's taps as `lowpass / g + g * highpass` makes a shelf, going
+/// from `1 / g` to `g`, that is at 0 dB at `1 / g` times its cutoff, in the analog
+/// prototype, and close enough with the bilinear transform, well below Nyquist. So
+/// the cutoff is moved up by `g` (see `OnePoleParams::slope_ratio`), for the knob's
+/// cutoff to be the pivot
+pub fn seesaw_gain(slope_db: f32, num_stages: usize) -> f32 {
+    10f32.powf(slope_db / (40. * num_stages as f32))
+}
+
+impl Mode {
+    /// Whether the cutoff is a shelf's corner, or a tilt's pivot, rather than where
+    /// the response rolls off, these can be set apart from the cutoff knob
+    pub fn pivots(self) -> bool {
+        matches!(
+            self,
+            Self::LowShelf | Self::HighShelf | Self::Tilt | Self::TiltEq
+        )
+    }
+
+    /// Whether the filters' own gain does anything, only the shelves have one
+    pub fn has_gain(self) -> bool {
+        matches!(self, Self::LowShelf | Self::HighShelf)
+    }
+
+    /// The mode the filter's coefficients are computed for
+    pub fn filter_mode(self) -> FilterMode {
+        match self {
+            Self::Lowpass | Self::Tilt | Self::TiltEq => FilterMode::LP,
+            Self::Highpass => FilterMode::HP,
+            Self::Allpass => FilterMode::AP,
+            Self::LowShelf => FilterMode::LSH,
+            Self::HighShelf => FilterMode::HSH,
+        }
+    }
+
+    /// A single stage's gain at DC, `gain` being the filters' own gain
+    pub fn dc_gain(self, gain: f32x2, blend: Blend) -> f32x2 {
+        match self {
+            Self::Lowpass | Self::Allpass | Self::HighShelf => Simd::splat(1.),
+            Self::Highpass => Simd::splat(0.),
+            Self::LowShelf => gain,
+            Self::Tilt => Simd::splat(1. - blend.tilt),
+            Self::TiltEq => Simd::splat(blend.seesaw.recip()),
+        }
+    }
+
+    pub fn output<F: Stage>(self) -> Output<F> {
+        match self {
+            Self::Tilt => Output::Tilt {
+                lowpass: F::output_function(FilterMode::LP),
+                highpass: F::output_function(FilterMode::HP),
+            },
+            Self::TiltEq => Output::Seesaw {
+                lowpass: F::output_function(FilterMode::LP),
+                highpass: F::output_function(FilterMode::HP),
+            },
+            mode => Output::Tap(F::output_function(mode.filter_mode())),
+        }
+    }
+}
+
+/// Which of a filter's taps make up its output
+pub enum Output<F> {
+    Tap(fn(&F) -> f32x2),
+    Tilt {
+        lowpass: fn(&F) -> f32x2,
+        highpass: fn(&F) -> f32x2,
+    },
+    Seesaw {
+        lowpass: fn(&F) -> f32x2,
+        highpass: fn(&F) -> f32x2,
+    },
+}
+
+// derives would require `F: Clone`
+impl<F> Clone for Output<F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F> Copy for Output<F> {}
+
+impl<F> Output<F> {
+    /// `blend` is only used by the blends, with `Output::Tilt`, a tilt of 0 yields
+    /// the lowpass output, and 1, the highpass
+    pub fn get(self, f: &F, blend: Blend) -> f32x2 {
+        match self {
+            Self::Tap(get_output) => get_output(f),
+            Self::Tilt { lowpass, highpass } => {
+                let tilt = Simd::splat(blend.tilt);
+                lowpass(f) * (Simd::splat(1.) - tilt) + highpass(f) * tilt
+            }
+            Self::Seesaw { lowpass, highpass } => {
+                let g = Simd::splat(blend.seesaw);
+                lowpass(f) / g + highpass(f) * g
+            }
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use core::ops::AddAssign;
+
+pub const CENTS_PER_OCTAVE: f32 = 1200.;
+
+/// Sum of the offsets, in cents, the cutoff modulation sources (key tracking, note
+/// expressions, the LFO, the envelope follower, the FM oscillator, the drift) apply
+/// to the knob's cutoff. They're all added up, then turned into a single factor,
+/// which `angular_cutoff` clamps to `MIN_FREQ..MAX_FREQ`, once for all of them.
+///
+/// Each source scales its own offset by its depth parameter. New ones only
+/// need to be added to the sum, wherever the others are
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Modulation {
+    cents: f32,
+}
+
+impl AddAssign<f32> for Modulation {
+    /// Adds a source's offset, in cents
+    fn add_assign(&mut self, cents: f32) {
+        self.cents += cents;
+    }
+}
+
+impl Modulation {
+    /// Factor to apply to the cutoff frequency
+    pub fn ratio(self) -> f32 {
+        (self.cents * (1. / CENTS_PER_OCTAVE)).exp2()
+    }
+}
@@ -0,0 +1,26 @@
+use nih_plug::prelude::*;
+use plugin_util::simd::*;
+
+/// What's heard at the output, to help with dialing in the cutoff
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Monitor {
+    /// The plugin's output, as usual
+    #[default]
+    Normal,
+    /// Only what the filters keep, unmixed, before auto gain and the ceiling
+    Band,
+    /// Only what the filters remove, the difference between the input and `Band`
+    Rejected,
+}
+
+impl Monitor {
+    /// The wet signal to output, out of the (latency compensated) `dry` signal, the
+    /// filters' raw output, `filtered`, and the fully processed wet signal, `wet`
+    pub fn wet(self, dry: f32x2, filtered: f32x2, wet: f32x2) -> f32x2 {
+        match self {
+            Self::Normal => wet,
+            Self::Band => filtered,
+            Self::Rejected => dry - filtered,
+        }
+    }
+}
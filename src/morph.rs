@@ -0,0 +1,153 @@
+use nih_plug::prelude::*;
+
+use atomic_float::AtomicF32;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::mode::Mode;
+
+/// Stored in place of a mode's index when there's none
+const NO_MODE: usize = usize::MAX;
+
+/// One end of the morph knob's sweep
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MorphPoint {
+    /// the cutoff knob's value, in Hz, before `range` maps it
+    pub cutoff: f32,
+    /// in dB
+    pub gain: f32,
+    pub mix: f32,
+    pub mode: Mode,
+}
+
+impl MorphPoint {
+    /// The point `t` of the way from `a` to `b`: the cutoff moves evenly in pitch, the
+    /// gain in dB, and the mix linearly. The mode can't be blended, it switches over
+    /// half way (where its usual crossfade takes over)
+    pub fn lerp(a: Self, b: Self, t: f32) -> Self {
+        let t = t.clamp(0., 1.);
+        Self {
+            cutoff: a.cutoff * (b.cutoff / a.cutoff).powf(t),
+            gain: a.gain + t * (b.gain - a.gain),
+            mix: a.mix + t * (b.mix - a.mix),
+            mode: if t < 0.5 { a.mode } else { b.mode },
+        }
+    }
+}
+
+/// A [`MorphPoint`], behind atomics
+#[derive(Default)]
+struct SharedPoint {
+    stored: AtomicBool,
+    cutoff: AtomicF32,
+    gain: AtomicF32,
+    mix: AtomicF32,
+    mode: AtomicUsize,
+}
+
+impl SharedPoint {
+    fn get(&self) -> Option<MorphPoint> {
+        self.stored.load(Ordering::Acquire).then(|| MorphPoint {
+            cutoff: self.cutoff.load(Ordering::Relaxed),
+            gain: self.gain.load(Ordering::Relaxed),
+            mix: self.mix.load(Ordering::Relaxed),
+            mode: Mode::from_index(self.mode.load(Ordering::Relaxed)),
+        })
+    }
+
+    fn set(&self, point: Option<MorphPoint>) {
+        self.stored.store(false, Ordering::Release);
+        if let Some(point) = point {
+            self.cutoff.store(point.cutoff, Ordering::Relaxed);
+            self.gain.store(point.gain, Ordering::Relaxed);
+            self.mix.store(point.mix, Ordering::Relaxed);
+            self.mode.store(point.mode.to_index(), Ordering::Relaxed);
+            self.stored.store(true, Ordering::Release);
+        }
+    }
+}
+
+/// The two snapshots, A and B, of the cutoff, gain, mix and mode, the `morph`
+/// knob sweeps between, once both are stored (from the editor).
+///
+/// Like MIDI learned CCs, the morph only drives the parameters' smoothers, the
+/// knobs stay where they are, until automating, or moving, them takes over again.
+/// The mode's knob has no smoother, so the mode the morph is at is kept in here,
+/// along with the knob's mode back then, to tell when it's moved. The snapshots
+/// are saved with the plugin's state, everything in here is lock-free
+pub struct Morph {
+    points: [SharedPoint; 2],
+    /// index of the mode the morph is at, `NO_MODE` if it hasn't moved yet
+    mode: AtomicUsize,
+    /// index of the mode knob's mode, when the morph last moved
+    knob_mode: AtomicUsize,
+}
+
+impl Default for Morph {
+    fn default() -> Self {
+        Self {
+            points: Default::default(),
+            mode: AtomicUsize::new(NO_MODE),
+            knob_mode: AtomicUsize::new(NO_MODE),
+        }
+    }
+}
+
+impl Morph {
+    /// Snapshot A (`0`) or B (`1`)
+    pub fn point(&self, i: usize) -> Option<MorphPoint> {
+        self.points[i].get()
+    }
+
+    pub fn store(&self, i: usize, point: MorphPoint) {
+        self.points[i].set(Some(point));
+    }
+
+    /// Where the morph is at, `t` of the way from A to B, if both are stored
+    pub fn at(&self, t: f32) -> Option<MorphPoint> {
+        Some(MorphPoint::lerp(self.point(0)?, self.point(1)?, t))
+    }
+
+    /// The mode the morph is at, unless the mode knob, now at `knob_mode`, moved since
+    pub fn mode(&self, knob_mode: Mode) -> Option<Mode> {
+        let index = self.mode.load(Ordering::Relaxed);
+        let moved = self.knob_mode.load(Ordering::Relaxed) != knob_mode.to_index();
+        (index != NO_MODE && !moved).then(|| Mode::from_index(index))
+    }
+
+    /// The morph moved to `mode`, the mode knob being at `knob_mode`
+    pub fn set_mode(&self, mode: Mode, knob_mode: Mode) {
+        self.knob_mode
+            .store(knob_mode.to_index(), Ordering::Relaxed);
+        self.mode.store(mode.to_index(), Ordering::Relaxed);
+    }
+}
+
+/// Each snapshot's cutoff (in Hz), gain (in dB), mix, and mode's index
+type SavedPoints = [Option<(f32, f32, f32, usize)>; 2];
+
+impl<'a> PersistentField<'a, SavedPoints> for Morph {
+    fn set(&self, new_value: SavedPoints) {
+        for (point, new) in self.points.iter().zip(new_value) {
+            // restored states with modes that don't exist leave the snapshot empty
+            let new = new.filter(|&(.., mode)| mode < Mode::variants().len()).map(
+                |(cutoff, gain, mix, mode)| MorphPoint {
+                    cutoff,
+                    gain,
+                    mix,
+                    mode: Mode::from_index(mode),
+                },
+            );
+            point.set(new);
+        }
+    }
+
+    fn map<F, R>(&self, f: F) -> R
+    where
+        F: Fn(&SavedPoints) -> R,
+    {
+        f(&[0, 1].map(|i| {
+            self.point(i)
+                .map(|p| (p.cutoff, p.gain, p.mix, p.mode.to_index()))
+        }))
+    }
+}
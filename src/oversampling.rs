@@ -0,0 +1,144 @@
+use plugin_util::simd::*;
+
+/// Number of non-zero taps in each polyphase branch of the half-band filter
+const TAPS: usize = 16;
+
+/// Non-zero, off-center taps of a 31-tap, Kaiser windowed (β = 7) half-band lowpass.
+/// The filter's center tap is 0.5, these taps sum up to 0.5, for unity DC gain
+const COEFFS: [f32; TAPS] = [
+    -0.0003943974,
+    0.0017603,
+    -0.005037465,
+    0.01162484,
+    -0.02374255,
+    0.04605905,
+    -0.09460322,
+    0.3143334,
+    0.3143334,
+    -0.09460322,
+    0.04605905,
+    -0.02374255,
+    0.01162484,
+    -0.005037465,
+    0.0017603,
+    -0.0003943974,
+];
+
+/// Delay, in input samples, of the branch of the filter containing only the center tap
+const CENTER: usize = TAPS / 2 - 1;
+
+/// Latency added by a 2x up/downsampling round trip, in samples at the lower rate
+const STAGE_LATENCY: usize = TAPS - 1;
+
+/// Largest supported oversampling factor, as a power of 2
+pub const MAX_FACTOR_LOG2: usize = 2;
+
+const MAX_LATENCY: usize = latency(MAX_FACTOR_LOG2);
+
+/// Convolves the last `TAPS` samples with `COEFFS`
+fn dot(history: &[f32x2; TAPS]) -> f32x2 {
+    history
+        .iter()
+        .zip(COEFFS)
+        .fold(Simd::splat(0.), |acc, (&x, c)| acc + x * Simd::splat(c))
+}
+
+pub fn push<const N: usize, T: Copy>(history: &mut [T; N], x: T) {
+    history.copy_within(..N - 1, 1);
+    history[0] = x;
+}
+
+/// Latency, in samples at the base rate, added by oversampling by `2^factor_log2`
+pub const fn latency(factor_log2: usize) -> usize {
+    // Each round trip delays by `STAGE_LATENCY` samples at its own input rate.
+    // Every stage after the first is padded by one sample at its input rate, so
+    // that the total is an integer number of samples at the base rate
+    let mut total = 0;
+    let mut i = 0;
+    while i < factor_log2 {
+        total += (STAGE_LATENCY + if i == 0 { 0 } else { 1 }) >> i;
+        i += 1;
+    }
+    total
+}
+
+/// Polyphase half-band 2x upsampler, delaying by 7.5 samples at its input rate
+#[derive(Default)]
+pub struct Upsampler {
+    history: [f32x2; TAPS],
+}
+
+impl Upsampler {
+    pub fn process(&mut self, x: f32x2) -> [f32x2; 2] {
+        push(&mut self.history, x);
+        // zero-stuffing halves the signal's level, hence the factor 2
+        [Simd::splat(2.) * dot(&self.history), self.history[CENTER]]
+    }
+}
+
+/// Polyphase half-band 2x downsampler
+#[derive(Default)]
+struct Downsampler {
+    even: [f32x2; TAPS],
+    odd: [f32x2; CENTER + 2],
+}
+
+impl Downsampler {
+    fn process(&mut self, [even, odd]: [f32x2; 2]) -> f32x2 {
+        push(&mut self.even, even);
+        push(&mut self.odd, odd);
+        dot(&self.even) + Simd::splat(0.5) * self.odd[CENTER + 1]
+    }
+}
+
+/// Stereo oversampler, running a processing callback at 1, 2 or 4 times the base rate
+#[derive(Default)]
+pub struct Oversampler {
+    up: [Upsampler; MAX_FACTOR_LOG2],
+    down: [Downsampler; MAX_FACTOR_LOG2],
+    /// padding for the 4x stage, see `latency`
+    pad: f32x2,
+    /// delay line for keeping the dry signal aligned with the oversampled one
+    dry: [f32x2; MAX_LATENCY + 1],
+}
+
+impl Oversampler {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Delays `x` by `latency(factor_log2)` samples
+    pub fn delay_dry(&mut self, factor_log2: usize, x: f32x2) -> f32x2 {
+        push(&mut self.dry, x);
+        self.dry[latency(factor_log2)]
+    }
+
+    /// Upsamples `x` by `2^factor_log2`, runs `f` on every upsampled sample,
+    /// and returns the result, downsampled back to the base rate
+    pub fn process(
+        &mut self,
+        factor_log2: usize,
+        x: f32x2,
+        mut f: impl FnMut(f32x2) -> f32x2,
+    ) -> f32x2 {
+        match factor_log2 {
+            0 => f(x),
+            1 => {
+                let [a, b] = self.up[0].process(x);
+                self.down[0].process([f(a), f(b)])
+            }
+            _ => {
+                let (up, inner_up) = self.up.split_at_mut(1);
+                let (down, inner_down) = self.down.split_at_mut(1);
+
+                let halves = up[0].process(x).map(|y| {
+                    let [a, b] = inner_up[0].process(y);
+                    let y = inner_down[0].process([f(a), f(b)]);
+                    core::mem::replace(&mut self.pad, y)
+                });
+
+                down[0].process(halves)
+            }
+        }
+    }
+}
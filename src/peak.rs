@@ -0,0 +1,59 @@
+use plugin_util::simd::*;
+
+/// Largest of the lanes of `x`. NaN lanes are ignored, unless they all are, so
+/// that a single broken sample doesn't take a meter down with it
+pub fn max_lane(x: f32x2) -> f32 {
+    x.reduce_max()
+}
+
+/// Peak absolute level of each lane of `samples`, a run of any length, 0 if
+/// it's empty, NaNs are ignored. [`max_lane`] of it is the peak across lanes
+pub fn peak(samples: &[f32x2]) -> f32x2 {
+    samples
+        .iter()
+        .fold(Simd::splat(0.), |peak, x| peak.simd_max(x.abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pairs of lanes, with both signs, zeros, and NaNs
+    const LANES: [[f32; 2]; 8] = [
+        [0., 0.],
+        [0.5, -0.25],
+        [-1., 0.75],
+        [-0.5, -0.125],
+        [2., 2.],
+        [f32::NAN, -0.5],
+        [0.25, f32::NAN],
+        [-3., 1e-30],
+    ];
+
+    #[test]
+    fn max_lane_matches_scalar() {
+        // `f32::max` ignores NaNs too
+        for lanes in LANES {
+            assert_eq!(
+                max_lane(Simd::from_array(lanes)),
+                lanes[0].max(lanes[1]),
+                "{lanes:?}"
+            );
+        }
+        assert!(max_lane(Simd::splat(f32::NAN)).is_nan());
+    }
+
+    #[test]
+    fn peak_matches_scalar() {
+        let samples = LANES.map(Simd::from_array);
+        for len in 0..=samples.len() {
+            let samples = &samples[..len];
+            let expected = [0, 1].map(|lane| {
+                samples
+                    .iter()
+                    .fold(0., |peak: f32, x| peak.max(x[lane].abs()))
+            });
+            assert_eq!(peak(samples).to_array(), expected, "first {len} samples");
+        }
+    }
+}
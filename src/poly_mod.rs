@@ -0,0 +1,95 @@
+use nih_plug::prelude::*;
+
+use atomic_float::AtomicF32;
+use core::sync::atomic::{AtomicI32, Ordering};
+
+/// Stored in place of a voice ID for targets that aren't modulated
+const NO_VOICE: i32 = i32::MIN;
+
+/// Parameters CLAP hosts can modulate per voice, their `poly_modulation_id`
+/// is their index in here
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PolyModTarget {
+    CutoffL,
+    CutoffR,
+    Resonance,
+}
+
+const TARGETS: [PolyModTarget; 3] = [
+    PolyModTarget::CutoffL,
+    PolyModTarget::CutoffR,
+    PolyModTarget::Resonance,
+];
+
+impl PolyModTarget {
+    pub fn id(self) -> u32 {
+        self as u32
+    }
+
+    fn from_id(id: u32) -> Option<Self> {
+        TARGETS.get(id as usize).copied()
+    }
+}
+
+/// Polyphonic modulation, from CLAP hosts, of the parameters in [`PolyModTarget`].
+///
+/// This is an effect, it has no voices of its own, so every voice's modulation
+/// applies to the whole signal, as a global one. Like key tracking, it's monophonic:
+/// with overlapping notes, each target follows the voice it most recently received
+/// an offset for, until that voice's note ends, then it goes back to the
+/// parameter's value, even if other voices are still held.
+///
+/// The offsets are normalized, and added to the parameters' normalized values.
+/// `process` writes them, and reads them back, so everything in here is lock-free
+pub struct PolyMod {
+    /// each target's offset, in `TARGETS`' order
+    offsets: [AtomicF32; 3],
+    /// each target's offset's voice, or `NO_VOICE`
+    voices: [AtomicI32; 3],
+}
+
+impl Default for PolyMod {
+    fn default() -> Self {
+        Self {
+            offsets: Default::default(),
+            voices: [const { AtomicI32::new(NO_VOICE) }; 3],
+        }
+    }
+}
+
+impl PolyMod {
+    /// Handles a `PolyModulation` event, those for unknown IDs are ignored
+    pub fn modulate(&self, poly_modulation_id: u32, voice_id: i32, offset: f32) {
+        if let Some(target) = PolyModTarget::from_id(poly_modulation_id) {
+            self.offsets[target as usize].store(offset, Ordering::Relaxed);
+            self.voices[target as usize].store(voice_id, Ordering::Relaxed);
+        }
+    }
+
+    /// Drops the offsets `voice_id`'s note was modulating its targets by
+    pub fn release(&self, voice_id: i32) {
+        for (offset, voice) in self.offsets.iter().zip(&self.voices) {
+            if voice
+                .compare_exchange(voice_id, NO_VOICE, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+            {
+                offset.store(0., Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn offset(&self, target: PolyModTarget) -> f32 {
+        self.offsets[target as usize].load(Ordering::Relaxed)
+    }
+
+    /// `plain`, a value of `target`'s parameter, `param`, moved by its offset
+    pub fn apply(&self, target: PolyModTarget, param: &FloatParam, plain: f32) -> f32 {
+        let offset = self.offset(target);
+        if offset == 0. {
+            plain
+        } else {
+            // clamped to the parameter's range
+            param.preview_plain(param.preview_normalized(plain) + offset)
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use nih_plug::prelude::*;
+
+use std::borrow::Cow;
+
+use crate::{editor::set_parameter, mode::Mode, OnePoleParams};
+
+/// A named set of parameter values. Parameters affecting neither the tone, nor
+/// the level (oversampling, bypass...) are left as they are when loading one
+#[derive(Clone)]
+pub struct Preset {
+    pub name: Cow<'static, str>,
+    /// in Hz
+    cutoff: f32,
+    gain: f32,
+    mode: Mode,
+    resonance: f32,
+    mix: f32,
+    key_track: f32,
+    slope: i32,
+    drive: f32,
+}
+
+pub(crate) const PRESETS: &[Preset] = &[
+    Preset {
+        name: Cow::Borrowed("Gentle LP"),
+        cutoff: 8000.,
+        gain: 0.,
+        mode: Mode::Lowpass,
+        resonance: 0.,
+        mix: 1.,
+        key_track: 0.,
+        slope: 1,
+        drive: 0.,
+    },
+    Preset {
+        name: Cow::Borrowed("Bright Shelf"),
+        cutoff: 4000.,
+        gain: 6.,
+        mode: Mode::HighShelf,
+        resonance: 0.,
+        mix: 1.,
+        key_track: 0.,
+        slope: 1,
+        drive: 0.,
+    },
+    Preset {
+        name: Cow::Borrowed("Telephone"),
+        cutoff: 500.,
+        gain: 0.,
+        mode: Mode::Highpass,
+        resonance: 0.3,
+        mix: 1.,
+        key_track: 0.,
+        slope: 2,
+        drive: 12.,
+    },
+];
+
+impl Preset {
+    /// The current values of `params`, under the name `name`
+    pub fn capture(params: &OnePoleParams, name: String) -> Self {
+        Self {
+            name: Cow::Owned(name),
+            cutoff: params.range.map(params.cutoff_l.unmodulated_plain_value()),
+            gain: params.gain.unmodulated_plain_value(),
+            mode: params.mode.unmodulated_plain_value(),
+            resonance: params.resonance.unmodulated_plain_value(),
+            mix: params.mix.unmodulated_plain_value(),
+            key_track: params.key_track.unmodulated_plain_value(),
+            slope: params.slope.unmodulated_plain_value(),
+            drive: params.drive.unmodulated_plain_value(),
+        }
+    }
+
+    /// Tab separated field names, in the order [`Self::to_line`] writes them
+    pub const HEADER: &'static str =
+        "name\tcutoff (Hz)\tgain (dB)\tmode\tresonance\tmix\tkey tracking\tslope\tdrive (dB)";
+
+    /// This preset as a single line of tab separated values, see [`Self::HEADER`]
+    pub fn to_line(&self) -> String {
+        let mode = Mode::variants()[self.mode.to_index()];
+        format!(
+            "{}\t{}\t{}\t{mode}\t{}\t{}\t{}\t{}\t{}",
+            self.name,
+            self.cutoff,
+            self.gain,
+            self.resonance,
+            self.mix,
+            self.key_track,
+            self.slope,
+            self.drive,
+        )
+    }
+
+    /// Inverse of [`Self::to_line`], returns `None` if any field is missing or invalid
+    pub fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t').map(str::trim);
+        let mut next = || fields.next();
+
+        let name = next()?.to_owned();
+        let cutoff = next()?.parse().ok()?;
+        let gain = next()?.parse().ok()?;
+        let mode = next()?;
+        let mode = Mode::variants().iter().position(|&v| v == mode)?;
+
+        Some(Self {
+            name: Cow::Owned(name),
+            cutoff,
+            gain,
+            mode: Mode::from_index(mode),
+            resonance: next()?.parse().ok()?,
+            mix: next()?.parse().ok()?,
+            key_track: next()?.parse().ok()?,
+            slope: next()?.parse().ok()?,
+            drive: next()?.parse().ok()?,
+        })
+    }
+
+    /// Loads this preset, notifying the host of every parameter change,
+    /// so this must be called from the GUI thread
+    pub fn apply(&self, params: &OnePoleParams, setter: &ParamSetter) {
+        // the knobs' values don't directly translate to frequencies
+        let cutoff = params.range.unmap(self.cutoff);
+        set_parameter(setter, &params.cutoff_l, cutoff);
+        set_parameter(setter, &params.cutoff_r, cutoff);
+        set_parameter(setter, &params.gain, self.gain);
+        set_parameter(setter, &params.mode, self.mode);
+        set_parameter(setter, &params.resonance, self.resonance);
+        set_parameter(setter, &params.mix, self.mix);
+        set_parameter(setter, &params.key_track, self.key_track);
+        set_parameter(setter, &params.slope, self.slope);
+        // presets are all made with every stage of their slope on
+        for stage in &params.stages {
+            set_parameter(setter, &stage.enabled, true);
+        }
+        set_parameter(setter, &params.drive, self.drive);
+    }
+}
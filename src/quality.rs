@@ -0,0 +1,42 @@
+use nih_plug::prelude::*;
+
+use crate::UpdateRate;
+
+/// Trades accuracy for CPU usage, overriding how often the coefficients are
+/// recomputed, and the oversampling setting
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Quality {
+    /// Cheapest. No oversampling, and the coefficients are recomputed at most
+    /// once per chunk (less than a millisecond, see `chunk_size`), even when
+    /// modulated, or automated, which can make fast modulation (e. g. audio rate
+    /// FM) sound stepped. The vintage option's dip is left out
+    Eco,
+    /// The coefficients are recomputed only as often as needed: every sample when
+    /// modulated, or while the cutoff's automated, every chunk while only the gain
+    /// is, only when they change otherwise. Oversampling follows its own setting
+    #[default]
+    Standard,
+    /// Most transparent, and the heaviest. The coefficients are ramped sample by
+    /// sample, whatever's moving, and the filters run at least 2x oversampled
+    High,
+}
+
+impl Quality {
+    /// `UpdateRate` to use, out of the one that would otherwise be
+    pub fn update_rate(self, needed: UpdateRate) -> UpdateRate {
+        match self {
+            Self::Eco => needed.min(UpdateRate::Chunk),
+            Self::Standard => needed,
+            Self::High => UpdateRate::Sample,
+        }
+    }
+
+    /// Oversampling factor to use, as a power of 2, out of the chosen one
+    pub fn oversampling_log2(self, chosen: usize) -> usize {
+        match self {
+            Self::Eco => 0,
+            Self::Standard => chosen,
+            Self::High => chosen.max(1),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use atomic_float::AtomicF32;
+
+use core::sync::atomic::Ordering;
+
+use crate::{MAX_FREQ, MIN_FREQ};
+
+/// Smallest allowed ratio between the top and bottom of the range, one octave
+const MIN_RATIO: f32 = 2.;
+
+/// Band spanned by the cutoff knobs, set by the `range_min` and `range_max`
+/// parameters, and the curve they follow, set by `skew`. It's shared between those parameters' callbacks, the cutoff
+/// parameters' display closures, and the audio thread, so that all of them
+/// always agree on which frequency a given knob position is.
+///
+/// The knobs themselves keep storing frequencies in the full `MIN_FREQ..MAX_FREQ`
+/// span, [`Self::map`] then moves them, exponentially, into the user's band
+pub struct CutoffRange {
+    min: AtomicF32,
+    max: AtomicF32,
+    /// exponent bending the knobs' position, in octaves, above 1, the lower
+    /// frequencies get more of the knobs' travel
+    skew: AtomicF32,
+}
+
+impl Default for CutoffRange {
+    fn default() -> Self {
+        Self {
+            min: AtomicF32::new(MIN_FREQ),
+            max: AtomicF32::new(MAX_FREQ),
+            skew: AtomicF32::new(1.),
+        }
+    }
+}
+
+impl CutoffRange {
+    pub fn set_min(&self, min: f32) {
+        self.min.store(min, Ordering::Relaxed);
+    }
+
+    pub fn set_max(&self, max: f32) {
+        self.max.store(max, Ordering::Relaxed);
+    }
+
+    pub fn set_skew(&self, skew: f32) {
+        self.skew.store(skew, Ordering::Relaxed);
+    }
+
+    /// The range's bounds. If the top is set below the bottom, or too close to
+    /// it, it's pushed back up, keeping the band at least `MIN_RATIO` wide
+    fn bounds(&self) -> (f32, f32) {
+        let min = self.min.load(Ordering::Relaxed).min(MAX_FREQ / MIN_RATIO);
+        let max = self.max.load(Ordering::Relaxed);
+        (min, max.clamp(min * MIN_RATIO, MAX_FREQ))
+    }
+
+    /// Maps a knob frequency, in `MIN_FREQ..MAX_FREQ`, to the one in the range at
+    /// the same relative position, in octaves, once skewed. With a skew of 1, the
+    /// knobs are spread evenly over the range's octaves
+    pub fn map(&self, knob_hz: f32) -> f32 {
+        let (min, max) = self.bounds();
+        let t = ((knob_hz / MIN_FREQ).ln() / (MAX_FREQ / MIN_FREQ).ln()).clamp(0., 1.);
+        min * (max / min).powf(t.powf(self.skew.load(Ordering::Relaxed)))
+    }
+
+    /// Inverse of [`Self::map`], frequencies outside of the range are clamped to it
+    pub fn unmap(&self, hz: f32) -> f32 {
+        let (min, max) = self.bounds();
+        let t = ((hz / min).ln() / (max / min).ln()).clamp(0., 1.);
+        let t = t.powf(self.skew.load(Ordering::Relaxed).recip());
+        MIN_FREQ * (MAX_FREQ / MIN_FREQ).powf(t)
+    }
+}
@@ -0,0 +1,197 @@
+use plugin_util::simd::*;
+
+use atomic_float::AtomicF32;
+use core::{
+    ops::{Add, Div, Mul, Sub},
+    sync::atomic::Ordering,
+};
+
+use crate::{
+    matched::MatchedOnePole,
+    mode::{Blend, Mode},
+    stage::Character,
+};
+
+#[derive(Clone, Copy)]
+pub struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    pub const ONE: Self = Self::real(1.);
+
+    pub const fn real(re: f64) -> Self {
+        Self { re, im: 0. }
+    }
+
+    /// `e^(-jw)`, the unit delay's response at the angular frequency `w`
+    pub fn delay(w: f64) -> Self {
+        Self {
+            re: w.cos(),
+            im: -w.sin(),
+        }
+    }
+
+    pub fn norm(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    /// In radians, in `-PI..=PI`
+    pub fn arg(self) -> f64 {
+        self.im.atan2(self.re)
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            re: self.re + rhs.re,
+            im: self.im + rhs.im,
+        }
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            re: self.re - rhs.re,
+            im: self.im - rhs.im,
+        }
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            re: self.re * rhs.re - self.im * rhs.im,
+            im: self.re * rhs.im + self.im * rhs.re,
+        }
+    }
+}
+
+impl Div for Complex {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        let d = rhs.re * rhs.re + rhs.im * rhs.im;
+        Self {
+            re: (self.re * rhs.re + self.im * rhs.im) / d,
+            im: (self.im * rhs.re - self.re * rhs.im) / d,
+        }
+    }
+}
+
+/// Response of the bilinear transform of the analog one-pole lowpass, with an angular
+/// cutoff of `w_c`, `delay` being the unit delay's response, at the frequency of interest
+pub fn bilinear_lowpass(w_c: f64, delay: Complex) -> Complex {
+    let g = (0.5 * w_c).tan();
+    Complex::real(g) * (Complex::ONE + delay)
+        / (Complex::real(1. + g) - Complex::real(1. - g) * delay)
+}
+
+/// Response of a single filter stage, of the given `character`, in the given `mode`.
+/// `gain` is the filters' own gain, and `blend`, the blending modes' settings
+pub fn stage(
+    character: Character,
+    mode: Mode,
+    w_c: f32,
+    gain: f32,
+    blend: Blend,
+    delay: Complex,
+) -> Complex {
+    let (lowpass, allpass) = match character {
+        Character::Clean | Character::Precise => {
+            let lowpass = bilinear_lowpass(f64::from(w_c), delay);
+            (lowpass, Complex::real(2.) * lowpass - Complex::ONE)
+        }
+        Character::Analog => {
+            let [p, b0, b1] = MatchedOnePole::coefficients(Simd::splat(w_c)).map(|c| c[0]);
+            let p = Complex::real(p.into());
+            let pole = Complex::ONE - p * delay;
+            let lowpass = (Complex::real(b0.into()) + Complex::real(b1.into()) * delay) / pole;
+            // see `MatchedOnePole`
+            (lowpass, (delay - p) / pole)
+        }
+    };
+    let highpass = Complex::ONE - lowpass;
+    let gain = Complex::real(gain.into());
+    let tilt = Complex::real(blend.tilt.into());
+    let seesaw = Complex::real(blend.seesaw.into());
+
+    match mode {
+        Mode::Lowpass => lowpass,
+        Mode::Highpass => highpass,
+        Mode::Allpass => allpass,
+        Mode::LowShelf => gain * lowpass + highpass,
+        Mode::HighShelf => lowpass + gain * highpass,
+        Mode::Tilt => (Complex::ONE - tilt) * lowpass + tilt * highpass,
+        Mode::TiltEq => lowpass / seesaw + seesaw * highpass,
+    }
+}
+
+/// What the response is computed at, besides the parameters: the rates the filters
+/// run at, and the modulation of the cutoff, see [`crate::OnePoleFilter::freq_ratio`]
+#[derive(Clone, Copy)]
+pub struct Operating {
+    /// of the host
+    pub sample_rate: f32,
+    /// `TAU / sample_rate`, at the oversampled rate
+    pub pi_tick: f32,
+    pub freq_ratio: f32,
+}
+
+/// The latest [`Operating`] state of the audio thread, so that the editor can compute
+/// the response on its own thread. Relaxed loads may tear it for a frame, at worst
+#[derive(Default)]
+pub struct Snapshot {
+    sample_rate: AtomicF32,
+    pi_tick: AtomicF32,
+    freq_ratio: AtomicF32,
+}
+
+impl Snapshot {
+    pub fn publish(&self, operating: Operating) {
+        self.sample_rate
+            .store(operating.sample_rate, Ordering::Relaxed);
+        self.pi_tick.store(operating.pi_tick, Ordering::Relaxed);
+        self.freq_ratio
+            .store(operating.freq_ratio, Ordering::Relaxed);
+    }
+
+    /// `None` before the audio thread has published anything
+    pub fn get(&self) -> Option<Operating> {
+        let sample_rate = self.sample_rate.load(Ordering::Relaxed);
+        (sample_rate > 0.).then(|| Operating {
+            sample_rate,
+            pi_tick: self.pi_tick.load(Ordering::Relaxed),
+            freq_ratio: self.freq_ratio.load(Ordering::Relaxed),
+        })
+    }
+}
+
+/// The plugin's response at a set of log-spaced frequencies, for drawing it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ResponseCurve {
+    /// in Hz, in ascending order
+    pub freqs: Vec<f32>,
+    /// linear gains, at each of `freqs`
+    pub magnitudes: Vec<f32>,
+    /// in radians, in `-PI..=PI`, at each of `freqs`, if asked for
+    pub phases: Option<Vec<f32>>,
+}
+
+/// `num_freqs` frequencies, evenly spaced in pitch, from `min` to `max`,
+/// both included, or just `min` if `num_freqs` is 1
+pub fn log_spaced(num_freqs: usize, min: f32, max: f32) -> Vec<f32> {
+    let step = (max / min).ln() / (num_freqs.max(2) - 1) as f32;
+    (0..num_freqs)
+        .map(|i| min * (step * i as f32).exp())
+        .collect()
+}
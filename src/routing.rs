@@ -0,0 +1,46 @@
+use nih_plug::prelude::*;
+use plugin_util::simd::*;
+
+/// What the main and send outputs carry, for send effect setups. Without a
+/// connected send output, the main one always gets the usual output
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Routing {
+    /// The usual output on the main output, the send output stays silent
+    #[default]
+    Main,
+    /// The dry signal on the main output, the usual one on the send output
+    #[name = "Wet to Send"]
+    WetToSend,
+    /// The usual output on the main output, the dry signal on the send output
+    #[name = "Dry to Send"]
+    DryToSend,
+}
+
+impl Routing {
+    /// The main and send outputs' samples, `dry` being the latency compensated
+    /// input, and `out`, the usual output, after the dry/wet mix
+    pub fn route(self, dry: f32x2, out: f32x2) -> (f32x2, f32x2) {
+        match self {
+            Self::Main => (out, Simd::splat(0.)),
+            Self::WetToSend => (dry, out),
+            Self::DryToSend => (out, dry),
+        }
+    }
+}
+
+/// The connected auxiliary outputs, each with as many channels as the main one
+pub struct AuxOutputs<'a, O: ?Sized> {
+    /// see [`Routing`]
+    pub send: Option<&'a mut O>,
+    /// the crossover's low and high bands, see [`Crossover`](crate::crossover::Crossover)
+    pub bands: Option<(&'a mut O, &'a mut O)>,
+}
+
+impl<O: ?Sized> AuxOutputs<'_, O> {
+    pub fn none() -> Self {
+        Self {
+            send: None,
+            bands: None,
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use core::f32::consts::TAU;
+
+use plugin_util::simd::*;
+
+/// Resonance the oscillation sets in at
+const THRESHOLD: f32 = 0.5;
+/// Level of the oscillation at full resonance, -6 dBFS, leaving the resonance
+/// loop's soft clipper some headroom
+const MAX_LEVEL: f32 = 0.5;
+
+/// Sine at the cutoff, in each lane, standing in for the filter self-oscillating.
+///
+/// A single pole can't self-oscillate in the audio range: with the one sample delay,
+/// the resonance loop's phase only reaches 180° above a quarter of the sample rate.
+/// So, with `self_osc` on, past `THRESHOLD`, the resonance feeds this into the loop,
+/// it then goes through the filters, and the feedback, like the input, at the
+/// oversampled rate, so that the harmonics the soft clipper adds don't alias
+#[derive(Default)]
+pub struct SelfOsc {
+    /// in radians, in `0..TAU`
+    phase: f32x2,
+}
+
+impl SelfOsc {
+    pub fn reset(&mut self) {
+        self.phase = Simd::splat(0.);
+    }
+
+    /// Linear level of the oscillation at `resonance`, in `0..=1`
+    pub fn level(resonance: f32) -> f32 {
+        MAX_LEVEL * ((resonance - THRESHOLD) / (1. - THRESHOLD)).max(0.)
+    }
+
+    /// Advances the oscillator by a sample, of `w` radians, and returns its value
+    pub fn next(&mut self, w: f32x2) -> f32x2 {
+        let value = self.phase.to_array().map(f32::sin);
+        // wrapped around, so that it doesn't lose precision over time
+        let phase = (self.phase + w)
+            .to_array()
+            .map(|phase| phase.rem_euclid(TAU));
+        self.phase = Simd::from_array(phase);
+        Simd::from_array(value)
+    }
+}
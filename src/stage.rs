@@ -0,0 +1,107 @@
+use nih_plug::prelude::*;
+use plugin_util::{filter::one_pole::FilterMode, simd::*};
+
+use crate::{double::DoubleOnePole, matched::MatchedOnePole, Filter, MAX_STAGES};
+
+/// Which filter topology runs
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Character {
+    /// Bilinear transform of the analog filter, as clean as it gets
+    #[default]
+    Clean,
+    /// Closer to the analog filter's response near Nyquist, see [`MatchedOnePole`]
+    Analog,
+    /// Same response as `Clean`, computed in double precision, see [`DoubleOnePole`].
+    /// Slightly more expensive, for long chains of plugins, with very low cutoffs
+    #[name = "Clean (64-bit)"]
+    Precise,
+}
+
+/// All kinds of filters, in series, only one of them is used at a time
+#[derive(Default)]
+pub struct Stages {
+    pub clean: [Filter; MAX_STAGES],
+    pub analog: [MatchedOnePole; MAX_STAGES],
+    pub double: [DoubleOnePole; MAX_STAGES],
+}
+
+impl Stages {
+    pub fn reset(&mut self) {
+        reset(&mut self.clean);
+        reset(&mut self.analog);
+        reset(&mut self.double);
+    }
+}
+
+/// Parameters of a single stage of the cascade
+#[derive(Params)]
+pub struct StageParams {
+    /// bypassed stages are skipped altogether, their state, and their smoothers, stay
+    /// put, so that they pick up where they left off once they're turned back on
+    #[id = "on"]
+    pub enabled: BoolParam,
+}
+
+impl StageParams {
+    /// Parameters of stage `n`, counting from 1
+    pub fn new(n: usize) -> Self {
+        Self {
+            enabled: BoolParam::new(format!("Stage {n}"), true),
+        }
+    }
+}
+
+pub fn reset<F: Stage>(stages: &mut [F]) {
+    for f in stages {
+        f.reset();
+    }
+}
+
+/// A first order filter section, processing a pair of channels.
+/// The `*_function` getters mirror `OnePole`'s own
+pub trait Stage: Sized {
+    /// The stages of this kind, out of `stages`
+    fn of(stages: &mut Stages) -> &mut [Self; MAX_STAGES];
+
+    fn update_function(mode: FilterMode) -> fn(&mut Self, f32x2, f32x2);
+
+    fn smoothing_update_function(mode: FilterMode) -> fn(&mut Self, f32x2, f32x2, usize);
+
+    fn output_function(mode: FilterMode) -> fn(&Self) -> f32x2;
+
+    fn update_smoothers(&mut self);
+
+    fn process(&mut self, x: f32x2);
+
+    fn reset(&mut self);
+}
+
+impl Stage for Filter {
+    fn of(stages: &mut Stages) -> &mut [Self; MAX_STAGES] {
+        &mut stages.clean
+    }
+
+    fn update_function(mode: FilterMode) -> fn(&mut Self, f32x2, f32x2) {
+        Self::get_update_function(mode)
+    }
+
+    fn smoothing_update_function(mode: FilterMode) -> fn(&mut Self, f32x2, f32x2, usize) {
+        Self::get_smoothing_update_function(mode)
+    }
+
+    fn output_function(mode: FilterMode) -> fn(&Self) -> f32x2 {
+        Self::get_output_function(mode)
+    }
+
+    fn update_smoothers(&mut self) {
+        Self::update_smoothers(self);
+    }
+
+    fn process(&mut self, x: f32x2) {
+        Self::process(self, x);
+    }
+
+    fn reset(&mut self) {
+        Self::reset(self);
+    }
+}
@@ -0,0 +1,89 @@
+use plugin_util::simd::*;
+
+use crate::oversampling::{push, Upsampler};
+
+/// Latency, in samples, added by the limiter, waiting for the detector, see `HOLD`
+pub const LATENCY: usize = 12;
+
+/// Number of the detector's last outputs the gain reduction covers. The peaks
+/// detected while the base rate sample `n` comes in lie 10.5 to 11.25 samples
+/// before it, once through both upsamplers. With `LATENCY`, the sample that's
+/// output, and both of its neighbours, lie within those the last `HOLD` outputs
+/// cover, so the gain reduction is in place all around it
+const HOLD: usize = 4;
+
+/// Brickwall limiter keeping the true peak level, that of the signal once
+/// converted to analog, under the ceiling. Peaks are detected on a 4x upsampled
+/// copy of the signal, catching most of those falling between samples, while the
+/// gain reduction is applied to the signal itself, at the base rate.
+///
+/// The gain drops at once, and comes back up exponentially, see
+/// [`Self::process`]
+pub struct TruePeakLimiter {
+    up: [Upsampler; 2],
+    /// gain needed by each of the last `HOLD` detected peaks
+    needed: [f32x2; HOLD],
+    gain: f32x2,
+    /// the signal being limited, waiting for the detector
+    lookahead: [f32x2; LATENCY + 1],
+    /// other signals, kept aligned with the limited one, see [`Self::align`]
+    aligned: [[f32x2; 2]; LATENCY + 1],
+}
+
+impl Default for TruePeakLimiter {
+    fn default() -> Self {
+        Self {
+            up: Default::default(),
+            needed: [Simd::splat(1.); HOLD],
+            gain: Simd::splat(1.),
+            lookahead: [Simd::splat(0.); LATENCY + 1],
+            aligned: [[Simd::splat(0.); 2]; LATENCY + 1],
+        }
+    }
+}
+
+impl TruePeakLimiter {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// 4x upsampled peak level of the last sample
+    fn detect(&mut self, x: f32x2) -> f32x2 {
+        let [up0, up1] = &mut self.up;
+        up0.process(x)
+            .into_iter()
+            .flat_map(|y| up1.process(y))
+            .fold(Simd::splat(0.), |peak, y| peak.simd_max(y.abs()))
+    }
+
+    /// Feeds `x` in, and returns the sample from `LATENCY` samples earlier,
+    /// limited to `±ceiling`, a linear gain. `release` is the fraction of the
+    /// remaining gain reduction kept every sample, on the way back up
+    pub fn process(&mut self, x: f32x2, ceiling: f32, release: f32) -> f32x2 {
+        let c = Simd::splat(ceiling);
+        let peak = self.detect(x);
+        push(&mut self.needed, (c / peak).simd_min(Simd::splat(1.)));
+
+        let target = self
+            .needed
+            .iter()
+            .fold(Simd::splat(1.), |min, &g| min.simd_min(g));
+        let released = target + Simd::splat(release) * (self.gain - target);
+        self.gain = target.simd_lt(self.gain).select(target, released);
+
+        push(&mut self.lookahead, x);
+        // the few peaks the detector misses are clipped
+        (self.lookahead[LATENCY] * self.gain).simd_clamp(-c, c)
+    }
+
+    /// Whether the gain is being reduced
+    pub fn limiting(&self) -> bool {
+        self.gain.simd_lt(Simd::splat(1.)).any()
+    }
+
+    /// Delays `signals` by as much as [`Self::process`] does
+    pub fn align(&mut self, signals: [f32x2; 2]) -> [f32x2; 2] {
+        push(&mut self.aligned, signals);
+        self.aligned[LATENCY]
+    }
+}
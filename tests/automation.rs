@@ -0,0 +1,123 @@
+//! With the eco quality, while the cutoff is automated, the coefficients are only
+//! recomputed once per chunk, this checks that the output stays close to that of
+//! recomputing them every sample, as happens when processing one sample at a time.
+//! The standard quality recomputes them every sample then, only while the cutoff
+//! moves, which must sound the same as not doing so while it doesn't. Fading the
+//! gain in, the output level must also rise evenly, in dB, sample after sample
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{automate, process, set_param, BLOCK_SIZE, SAMPLE_RATE};
+
+const NUM_SAMPLES: usize = 8192;
+const TOLERANCE: f32 = 1e-3;
+
+/// Normalized value of the quality parameter
+const ECO: f32 = 0.;
+
+fn input() -> Vec<[f32; 2]> {
+    let w = 2. * core::f32::consts::PI * 1000. / SAMPLE_RATE;
+    (0..NUM_SAMPLES)
+        .map(|i| {
+            let x = 0.5 * (w * i as f32).sin();
+            [x, -x]
+        })
+        .collect()
+}
+
+fn render(block_size: usize) -> Vec<[f32; 2]> {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "quality", |_| ECO);
+    set_param(&plugin, "cutoff", |_| 0.2);
+    plugin.prepare(SAMPLE_RATE);
+    automate(&plugin, "cutoff", 0.8);
+
+    let mut samples = input();
+    for block in samples.chunks_mut(block_size) {
+        plugin.process_block(block);
+    }
+    samples
+}
+
+/// The standard quality's output, with the cutoff static, but, with `smoothing`,
+/// automated to where it already is, before every block, so that its smoother runs
+fn render_static(smoothing: bool) -> Vec<[f32; 2]> {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "cutoff", |_| 0.4);
+    plugin.prepare(SAMPLE_RATE);
+
+    let mut samples = input();
+    for block in samples.chunks_mut(BLOCK_SIZE) {
+        if smoothing {
+            automate(&plugin, "cutoff", 0.4);
+        }
+        plugin.process_block(block);
+    }
+    samples
+}
+
+#[test]
+fn automation_matches_per_sample_updates() {
+    let chunked = render(BLOCK_SIZE);
+    let per_sample = render(1);
+    for (i, (a, b)) in chunked.iter().zip(&per_sample).enumerate() {
+        for lane in 0..2 {
+            assert!(
+                (a[lane] - b[lane]).abs() < TOLERANCE,
+                "sample {i}, lane {lane}: {} updating once per chunk, {} every sample",
+                a[lane],
+                b[lane],
+            );
+        }
+    }
+}
+
+#[test]
+fn per_sample_updates_match_static_ones() {
+    let (block, per_sample) = (render_static(false), render_static(true));
+    for (i, (a, b)) in block.iter().zip(&per_sample).enumerate() {
+        for lane in 0..2 {
+            assert!(
+                (a[lane] - b[lane]).abs() < 1e-5,
+                "sample {i}, lane {lane}: {} once per block, {} every sample",
+                a[lane],
+                b[lane],
+            );
+        }
+    }
+}
+
+#[test]
+fn gain_fades_evenly_in_db() {
+    let mut plugin = OnePoleFilter::default();
+    // lowpass, passing DC
+    set_param(&plugin, "mode", |_| 0.);
+    // as an output level, from -30 dB
+    // SAFETY: nothing else is touching the parameters
+    set_param(&plugin, "gain_position", |p| unsafe {
+        p.string_to_normalized_value("Post").unwrap()
+    });
+    set_param(&plugin, "gain", |_| 0.);
+    plugin.prepare(SAMPLE_RATE);
+
+    // the filters settle on DC first
+    let mut samples = vec![[0.5; 2]; NUM_SAMPLES];
+    process(&mut plugin, &mut samples);
+
+    // to 0 dB
+    automate(&plugin, "gain", 0.5);
+    process(&mut plugin, &mut samples);
+
+    let levels: Vec<f32> = samples.iter().map(|s| 20. * s[0].log10()).collect();
+    // over the default 20 ms of smoothing
+    let slope = 30. / (0.02 * SAMPLE_RATE);
+    for (i, pair) in levels.windows(2).enumerate() {
+        let step = pair[1] - pair[0];
+        assert!(
+            (-1e-3..slope * 1.01 + 1e-3).contains(&step),
+            "sample {i}: level moved by {step} dB, {slope} dB at most expected",
+        );
+    }
+    assert!((levels[NUM_SAMPLES - 1] - 20. * 0.5f32.log10()).abs() < 1e-2);
+}
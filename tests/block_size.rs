@@ -0,0 +1,55 @@
+//! With static settings, the coefficients are only updated when they change, this
+//! checks that skipping these updates leaves the output as it would be otherwise,
+//! by comparing the output of small blocks, of which all but the first skip them,
+//! to that of a single block, updating them once
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{set_param, SAMPLE_RATE};
+
+const NUM_SAMPLES: usize = 8192;
+const SMALL_BLOCK: usize = 64;
+
+fn render(block_size: usize) -> Vec<[f32; 2]> {
+    let mut plugin = OnePoleFilter::default();
+    // highpass, with resonance, so that the filters' state matters
+    set_param(&plugin, "mode", |_| 0.2);
+    set_param(&plugin, "resonance", |_| 0.5);
+    plugin.prepare(SAMPLE_RATE);
+
+    // a noise burst, then silence
+    let mut seed = 1u32;
+    let mut samples: Vec<[f32; 2]> = (0..NUM_SAMPLES)
+        .map(|i| {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            let x = if i < NUM_SAMPLES / 2 {
+                seed as f32 / u32::MAX as f32 - 0.5
+            } else {
+                0.
+            };
+            [x, -x]
+        })
+        .collect();
+
+    for block in samples.chunks_mut(block_size) {
+        plugin.process_block(block);
+    }
+    samples
+}
+
+#[test]
+fn output_is_independent_of_block_size() {
+    let small = render(SMALL_BLOCK);
+    let whole = render(NUM_SAMPLES);
+    for (i, (a, b)) in small.iter().zip(&whole).enumerate() {
+        for lane in 0..2 {
+            assert!(
+                (a[lane] - b[lane]).abs() < 1e-6,
+                "sample {i}, lane {lane}: {} with small blocks, {} otherwise",
+                a[lane],
+                b[lane],
+            );
+        }
+    }
+}
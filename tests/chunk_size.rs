@@ -0,0 +1,60 @@
+//! Shorter chunks follow automation more closely, whatever their length, the
+//! last, partial, chunk of each block included
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{automate, set_param, BLOCK_SIZE, SAMPLE_RATE};
+
+const NUM_SAMPLES: usize = 8192;
+const TOLERANCE: f32 = 1e-3;
+
+/// Normalized values of the `chunk_size` parameter, 4, 7, and 32 samples
+const SHORTEST: f32 = 0.;
+/// doesn't divide `BLOCK_SIZE`
+const UNEVEN: f32 = 3. / 28.;
+const LONGEST: f32 = 1.;
+
+/// Normalized value of the quality parameter
+const ECO: f32 = 0.;
+
+fn render(chunk_size: f32, block_size: usize) -> Vec<[f32; 2]> {
+    let mut plugin = OnePoleFilter::default();
+    // the standard quality recomputes an automated cutoff's coefficients every sample
+    set_param(&plugin, "quality", |_| ECO);
+    set_param(&plugin, "cutoff", |_| 0.2);
+    set_param(&plugin, "chunk_size", |_| chunk_size);
+    plugin.prepare(SAMPLE_RATE);
+    automate(&plugin, "cutoff", 0.8);
+
+    let w = 2. * core::f32::consts::PI * 1000. / SAMPLE_RATE;
+    let mut samples: Vec<[f32; 2]> = (0..NUM_SAMPLES)
+        .map(|i| [0.5 * (w * i as f32).sin(); 2])
+        .collect();
+
+    for block in samples.chunks_mut(block_size) {
+        plugin.process_block(block);
+    }
+    samples
+}
+
+/// Largest difference from updating the coefficients every sample
+fn max_error(chunk_size: f32) -> f32 {
+    let per_sample = render(LONGEST, 1);
+    render(chunk_size, BLOCK_SIZE)
+        .iter()
+        .zip(&per_sample)
+        .map(|(a, b)| (a[0] - b[0]).abs())
+        .fold(0., f32::max)
+}
+
+#[test]
+fn shorter_chunks_follow_automation_more_closely() {
+    let (shortest, uneven, longest) = (max_error(SHORTEST), max_error(UNEVEN), max_error(LONGEST));
+    assert!(longest < TOLERANCE, "{longest}");
+    assert!(uneven < TOLERANCE, "{uneven}");
+    assert!(
+        shortest < longest,
+        "{shortest} with 4 samples, {longest} with 32"
+    );
+}
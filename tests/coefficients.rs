@@ -0,0 +1,59 @@
+//! Checks that the cutoff knob's ends map to the `MIN_FREQ..MAX_FREQ` range,
+//! and how the skew bends the curve in between
+
+use core::f32::consts::TAU;
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{set_param, SAMPLE_RATE};
+
+const MIN_FREQ: f32 = 13.;
+const MAX_FREQ: f32 = 21000.;
+
+/// Angular cutoffs, in radians per sample, of both channels, with the cutoff
+/// knob at `knob_hz`, and the skew at `skew`
+fn angular_cutoffs(knob_hz: f32, skew: f32) -> [f32; 2] {
+    let mut plugin = OnePoleFilter::default();
+    // the knob's display follows the skew, so the cutoff's set first
+    for (id, value) in [
+        ("cutoff", format!("{knob_hz} Hz")),
+        ("skew", skew.to_string()),
+    ] {
+        // SAFETY: nothing else is touching the parameters
+        set_param(&plugin, id, |p| unsafe {
+            p.string_to_normalized_value(&value).unwrap()
+        });
+    }
+    plugin.prepare(SAMPLE_RATE);
+    plugin.process_block(&mut [[0.; 2]; 64]);
+    plugin.coefficients().map(|c| c.w_c)
+}
+
+fn assert_cutoffs(knob_hz: f32, skew: f32, freq: f32) {
+    let expected = TAU * freq / SAMPLE_RATE;
+    for w_c in angular_cutoffs(knob_hz, skew) {
+        assert!(
+            (w_c / expected - 1.).abs() < 1e-4,
+            "knob at {knob_hz} Hz, skew of {skew}: {w_c} rad/sample, expected {expected}",
+        );
+    }
+}
+
+#[test]
+fn cutoff_range() {
+    assert_cutoffs(MIN_FREQ, 1., MIN_FREQ);
+    assert_cutoffs(MAX_FREQ, 1., MAX_FREQ);
+}
+
+#[test]
+fn skew_bends_toward_the_low_end() {
+    // halfway through the knob's octaves
+    let center = (MIN_FREQ * MAX_FREQ).sqrt();
+    assert_cutoffs(center, 1., center);
+    // a quarter of the way
+    assert_cutoffs(center, 2., MIN_FREQ * (MAX_FREQ / MIN_FREQ).powf(0.25));
+    // the ends stay put
+    assert_cutoffs(MIN_FREQ, 2., MIN_FREQ);
+    assert_cutoffs(MAX_FREQ, 2., MAX_FREQ);
+}
@@ -0,0 +1,50 @@
+//! Helpers shared by the integration tests
+
+// not every test uses all of them
+#![allow(dead_code)]
+
+use nih_plug::prelude::*;
+use one_pole::OnePoleFilter;
+
+pub const SAMPLE_RATE: f32 = 44100.;
+pub const BLOCK_SIZE: usize = 512;
+
+pub fn find_param(plugin: &OnePoleFilter, id: &str) -> ParamPtr {
+    plugin
+        .params()
+        .param_map()
+        .into_iter()
+        .find_map(|(param_id, ptr, _)| (param_id == id).then_some(ptr))
+        .unwrap_or_else(|| panic!("no parameter with id {id}"))
+}
+
+/// Sets the parameter with id `id` to the normalized value returned by `value`
+pub fn set_param(plugin: &OnePoleFilter, id: &str, value: impl FnOnce(ParamPtr) -> f32) {
+    let param = find_param(plugin, id);
+    // SAFETY: nothing else is touching the parameters
+    unsafe { param.set_normalized_value(value(param)) };
+}
+
+/// Sets the parameter with id `id` to `normalized` while processing audio, the
+/// way hosts automate parameters, its smoother then glides towards the new value
+pub fn automate(plugin: &OnePoleFilter, id: &str, normalized: f32) {
+    let param = find_param(plugin, id);
+    // SAFETY: same as above
+    unsafe {
+        param.set_normalized_value(normalized);
+        param.update_smoother(SAMPLE_RATE, false);
+    }
+}
+
+/// Processes `samples` in place, `BLOCK_SIZE` samples at a time
+pub fn process(plugin: &mut OnePoleFilter, samples: &mut [[f32; 2]]) {
+    process_at(plugin, samples, SAMPLE_RATE);
+}
+
+/// [`process`], the host running at `sample_rate`, which the plugin follows
+/// from the first block on, whatever it was prepared for
+pub fn process_at(plugin: &mut OnePoleFilter, samples: &mut [[f32; 2]], sample_rate: f32) {
+    for block in samples.chunks_mut(BLOCK_SIZE) {
+        plugin.process_block_at(block, sample_rate);
+    }
+}
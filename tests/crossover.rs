@@ -0,0 +1,73 @@
+//! The crossover's bands must add back up to a flat response, each being
+//! 6 dB down at the crossover frequency
+
+use core::f32::consts::PI;
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{set_param, BLOCK_SIZE, SAMPLE_RATE};
+
+const NUM_SAMPLES: usize = 16384;
+
+/// Default crossover frequency
+const CROSSOVER_FREQ: f32 = 1000.;
+
+/// Levels, in dB relative to the input, of the low band, the high band,
+/// and their sum, for a sine at `freq`
+fn measure(freq: f32) -> [f32; 3] {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "crossover", |_| 1.);
+    // the output is the input, so the bands split the input
+    set_param(&plugin, "mix", |_| 0.);
+    plugin.prepare(SAMPLE_RATE);
+
+    let w = 2. * PI * freq / SAMPLE_RATE;
+    let input: Vec<f32> = (0..NUM_SAMPLES)
+        .map(|i| 0.5 * (w * i as f32).sin())
+        .collect();
+    let mut samples: Vec<[f32; 2]> = input.iter().map(|&x| [x, x]).collect();
+    let (mut low, mut high) = (samples.clone(), samples.clone());
+
+    for ((samples, low), high) in samples
+        .chunks_mut(BLOCK_SIZE)
+        .zip(low.chunks_mut(BLOCK_SIZE))
+        .zip(high.chunks_mut(BLOCK_SIZE))
+    {
+        plugin.process_block_with_bands(samples, low, high);
+    }
+
+    let settled = NUM_SAMPLES / 2..;
+    let rms = |x: &mut dyn Iterator<Item = f32>| {
+        let (sum, n) = x.fold((0., 0), |(sum, n), s| (sum + s * s, n + 1));
+        (sum / n as f32).sqrt()
+    };
+    let input_rms = rms(&mut input[settled.clone()].iter().copied());
+    let db = |x: &mut dyn Iterator<Item = f32>| 20. * (rms(x) / input_rms).log10();
+
+    let (low, high) = (&low[settled.clone()], &high[settled]);
+    [
+        db(&mut low.iter().map(|s| s[0])),
+        db(&mut high.iter().map(|s| s[0])),
+        db(&mut low.iter().zip(high).map(|(l, h)| l[0] + h[0])),
+    ]
+}
+
+#[test]
+fn bands_sum_flat() {
+    for freq in [100., 300., CROSSOVER_FREQ, 3000., 12000.] {
+        let [.., sum] = measure(freq);
+        assert!(sum.abs() < 0.1, "{freq} Hz: bands sum up to {sum} dB");
+    }
+}
+
+#[test]
+fn bands_cross_at_minus_6_db() {
+    let [low, high, _] = measure(CROSSOVER_FREQ);
+    for (band, level) in [("low", low), ("high", high)] {
+        assert!(
+            (level + 6.02).abs() < 0.1,
+            "{band} band at {level} dB at the crossover frequency",
+        );
+    }
+}
@@ -0,0 +1,40 @@
+//! Feeds a DC step through the filter, with the DC blocker on and off
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{process, set_param, SAMPLE_RATE};
+
+const STEP: f32 = 0.5;
+/// one second, a couple hundred time constants of the blocker's highpass
+const NUM_SAMPLES: usize = SAMPLE_RATE as usize;
+
+/// Returns the largest output sample, in absolute value, over the last block
+fn settled_output(dc_block: bool) -> f32 {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "dc_block", |_| if dc_block { 1. } else { 0. });
+    plugin.prepare(SAMPLE_RATE);
+
+    let mut samples = vec![[STEP; 2]; NUM_SAMPLES];
+    process(&mut plugin, &mut samples);
+
+    samples[NUM_SAMPLES - common::BLOCK_SIZE..]
+        .iter()
+        .flatten()
+        .fold(0., |peak: f32, s| peak.max(s.abs()))
+}
+
+#[test]
+fn dc_step_settles_to_zero() {
+    let peak = settled_output(true);
+    assert!(peak < 1e-4, "output still at {peak} after a second");
+}
+
+#[test]
+fn dc_passes_through_lowpass_without_blocker() {
+    let peak = settled_output(false);
+    assert!(
+        (peak - STEP).abs() < 1e-3,
+        "output at {peak}, expected {STEP}"
+    );
+}
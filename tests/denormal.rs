@@ -0,0 +1,49 @@
+//! As an impulse's response decays into silence, the output must never go
+//! subnormal. Flushing to zero brings it all the way down to exact silence,
+//! the noise keeps it at its own level instead
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{process, set_param, SAMPLE_RATE};
+
+/// Long enough for the slowest decay to go below the smallest normal float
+const NUM_SAMPLES: usize = 4 * 44100;
+
+/// Normalized values of the denormal mode parameter
+const FTZ: f32 = 0.5;
+const NOISE: f32 = 1.;
+
+/// An impulse, then silence, through the lowest cutoff, which decays the slowest
+fn render(denormal_mode: f32) -> Vec<[f32; 2]> {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "denormal_mode", |_| denormal_mode);
+    set_param(&plugin, "cutoff", |_| 0.);
+    plugin.prepare(SAMPLE_RATE);
+
+    let mut samples = vec![[0.; 2]; NUM_SAMPLES];
+    samples[0] = [1., -1.];
+    process(&mut plugin, &mut samples);
+    samples
+}
+
+fn assert_never_subnormal(samples: &[[f32; 2]]) {
+    for (i, sample) in samples.iter().enumerate() {
+        assert!(
+            !sample.iter().any(|x| x.is_subnormal()),
+            "sample {i} is subnormal: {sample:?}",
+        );
+    }
+}
+
+#[test]
+fn flushed_to_exact_silence() {
+    let samples = render(FTZ);
+    assert_never_subnormal(&samples);
+    assert_eq!(samples[NUM_SAMPLES - 1], [0.; 2]);
+}
+
+#[test]
+fn kept_above_the_denormal_range_by_noise() {
+    assert_never_subnormal(&render(NOISE));
+}
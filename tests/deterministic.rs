@@ -0,0 +1,40 @@
+//! With the deterministic mode on, every random source restarts from its seed on
+//! resets, so that renders can be compared to golden files, even with the
+//! denormal noise, which otherwise keeps running across resets
+
+use nih_plug::prelude::*;
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{process, set_param, SAMPLE_RATE};
+
+/// a second, for the drift to move a bit
+const NUM_SAMPLES: usize = SAMPLE_RATE as usize;
+
+fn render(plugin: &mut OnePoleFilter, amplitude: f32) -> Vec<[f32; 2]> {
+    let w = 2. * core::f32::consts::PI * 1000. / SAMPLE_RATE;
+    let mut samples: Vec<[f32; 2]> = (0..NUM_SAMPLES)
+        .map(|i| [amplitude * (w * i as f32).sin(); 2])
+        .collect();
+    process(plugin, &mut samples);
+    samples
+}
+
+#[test]
+fn renders_are_reproducible() {
+    let mut plugin = OnePoleFilter::default();
+    plugin.set_deterministic(true);
+    set_param(&plugin, "drift", |_| 1.);
+    // the last one, `DenormalMode::Noise`
+    set_param(&plugin, "denormal_mode", |_| 1.);
+    plugin.prepare(SAMPLE_RATE);
+
+    let first = render(&mut plugin, 0.5);
+    // something else, in between, moving the noise generator along
+    render(&mut plugin, 0.1);
+    plugin.reset();
+    plugin.prepare(SAMPLE_RATE);
+    let second = render(&mut plugin, 0.5);
+
+    assert!(first == second, "renders differ after a reset");
+}
@@ -0,0 +1,85 @@
+//! The main output can be dithered down to 16 bits, it's left untouched otherwise
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{process, set_param, SAMPLE_RATE};
+
+/// Normalized values of the `dither` parameter
+const OFF: f32 = 0.;
+const TPDF: f32 = 0.5;
+const SHAPED: f32 = 1.;
+
+/// A 16 bit LSB
+const LSB: f32 = 1. / 32768.;
+
+const NUM_SAMPLES: usize = SAMPLE_RATE as usize / 10;
+
+fn render(dither: f32, input: f32) -> Vec<[f32; 2]> {
+    let mut plugin = OnePoleFilter::default();
+    plugin.set_deterministic(true);
+    set_param(&plugin, "dither", |_| dither);
+    plugin.prepare(SAMPLE_RATE);
+
+    let mut samples = vec![[input; 2]; NUM_SAMPLES];
+    process(&mut plugin, &mut samples);
+    samples
+}
+
+#[test]
+fn off_by_default() {
+    let samples = render(OFF, 0.);
+    assert!(samples.iter().flatten().all(|&x| x == 0.));
+}
+
+#[test]
+fn tpdf_stays_within_an_lsb() {
+    let samples = render(TPDF, 0.);
+    assert!(samples.iter().flatten().all(|&x| x.abs() <= LSB));
+    assert!(samples.iter().flatten().any(|&x| x != 0.));
+
+    // each channel gets noise of its own
+    assert!(samples.iter().any(|[l, r]| l != r));
+}
+
+#[test]
+fn noise_shaping_rounds_to_16_bits() {
+    let samples = render(SHAPED, 0.3);
+    for &x in samples.iter().flatten() {
+        let steps = x / LSB;
+        assert_eq!(steps, steps.round(), "{x} isn't a multiple of an LSB");
+    }
+
+    // the rounding errors cancel out on average, once the filters have settled
+    let mean = |samples: &[[f32; 2]]| {
+        let settled = &samples[NUM_SAMPLES / 2..];
+        settled.iter().map(|[l, _]| l).sum::<f32>() / settled.len() as f32
+    };
+    let (dithered, exact) = (mean(&samples), mean(&render(OFF, 0.3)));
+    assert!(
+        (dithered - exact).abs() < LSB,
+        "{dithered}, instead of {exact}"
+    );
+}
+
+#[test]
+fn reproducible() {
+    assert!(render(TPDF, 0.) == render(TPDF, 0.));
+}
+
+#[test]
+fn every_channel_gets_noise_of_its_own() {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "dither", |_| TPDF);
+    plugin.prepare(SAMPLE_RATE);
+
+    let mut channels = vec![vec![0.; NUM_SAMPLES]; 6];
+    let mut slices: Vec<&mut [f32]> = channels.iter_mut().map(Vec::as_mut_slice).collect();
+    plugin.process_channels(&mut slices);
+
+    for (i, a) in channels.iter().enumerate() {
+        for b in &channels[i + 1..] {
+            assert_ne!(a, b, "channel {i}'s noise shows up in another one");
+        }
+    }
+}
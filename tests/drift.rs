@@ -0,0 +1,44 @@
+//! The drift is random, but has to be the same on every render, for offline
+//! bounces to match, this checks that it restarts from the same point on resets
+
+use nih_plug::prelude::*;
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{process, set_param, SAMPLE_RATE};
+
+/// A few seconds, for the drift to wander away from the knob's value
+const NUM_SAMPLES: usize = 4 * 44100;
+
+fn render(plugin: &mut OnePoleFilter) -> Vec<[f32; 2]> {
+    let w = 2. * core::f32::consts::PI * 1000. / SAMPLE_RATE;
+    let mut samples: Vec<[f32; 2]> = (0..NUM_SAMPLES)
+        .map(|i| {
+            let x = 0.5 * (w * i as f32).sin();
+            [x, x]
+        })
+        .collect();
+    process(plugin, &mut samples);
+    samples
+}
+
+#[test]
+fn drift_is_reproducible() {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "drift", |_| 1.);
+    plugin.prepare(SAMPLE_RATE);
+
+    let first = render(&mut plugin);
+    // as hosts do before bouncing
+    plugin.reset();
+    plugin.prepare(SAMPLE_RATE);
+    let second = render(&mut plugin);
+    assert!(first == second, "the drift differs after a reset");
+
+    let mut still = OnePoleFilter::default();
+    still.prepare(SAMPLE_RATE);
+    assert!(
+        render(&mut still) != first,
+        "the drift doesn't move the cutoff"
+    );
+}
@@ -0,0 +1,54 @@
+//! Freezing holds the filters' output, releasing it picks up from there, without a click
+
+use core::f32::consts::PI;
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{automate, process, SAMPLE_RATE};
+
+/// longer than the crossfades in and out
+const NUM_SAMPLES: usize = SAMPLE_RATE as usize / 10;
+const FREQ: f32 = 200.;
+
+fn sine(start: usize) -> Vec<[f32; 2]> {
+    let w = 2. * PI * FREQ / SAMPLE_RATE;
+    (start..start + NUM_SAMPLES)
+        .map(|i| [0.5 * (w * i as f32).sin(); 2])
+        .collect()
+}
+
+/// Biggest difference between two consecutive samples
+fn max_step(samples: &[[f32; 2]]) -> f32 {
+    samples
+        .windows(2)
+        .map(|w| (w[1][0] - w[0][0]).abs())
+        .fold(0., f32::max)
+}
+
+#[test]
+fn holds_then_resumes_smoothly() {
+    let mut plugin = OnePoleFilter::default();
+    plugin.prepare(SAMPLE_RATE);
+
+    let mut before = sine(0);
+    process(&mut plugin, &mut before);
+    // how fast the output moves on its own
+    let limit = 2. * max_step(&before);
+
+    automate(&plugin, "freeze", 1.);
+    let mut frozen = sine(NUM_SAMPLES);
+    process(&mut plugin, &mut frozen);
+    let held = frozen[NUM_SAMPLES - 1];
+    assert!(frozen[NUM_SAMPLES / 2..].iter().all(|&s| s == held));
+
+    automate(&plugin, "freeze", 0.);
+    let mut after = sine(2 * NUM_SAMPLES);
+    process(&mut plugin, &mut after);
+    // it moves again
+    assert!(after[NUM_SAMPLES / 2..].iter().any(|&s| s != held));
+
+    let all: Vec<_> = [before, frozen, after].concat();
+    let step = max_step(&all);
+    assert!(step <= limit, "jumped by {step}, more than {limit}");
+}
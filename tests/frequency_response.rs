@@ -0,0 +1,325 @@
+//! Compares the filter's measured magnitude response, with a single stage, to
+//! the analytic response of a bilinear-transformed, analog one-pole filter
+
+use core::f32::consts::PI;
+
+use nih_plug::prelude::*;
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{automate, find_param, process, set_param, SAMPLE_RATE};
+
+const CUTOFF: f32 = 1000.;
+/// long enough for the filter to settle, the second half is measured
+const NUM_SAMPLES: usize = 44100;
+
+const TOLERANCE_DB: f32 = 0.5;
+const TEST_FREQS: [f32; 5] = [100., 300., 1000., 3000., 8000.];
+
+/// Index of each mode in the `mode` parameter
+const LOWPASS: usize = 0;
+const HIGHPASS: usize = 1;
+const ALLPASS: usize = 2;
+const LOW_SHELF: usize = 3;
+const HIGH_SHELF: usize = 4;
+const TILT: usize = 5;
+const TILT_EQ: usize = 6;
+const NUM_MODES: usize = 7;
+
+/// Corner of the highpass' damping lowpass, at full damping
+const DAMPING_FREQ: f32 = 2000.;
+
+/// Quiet enough for the resonance loop's soft clipper to stay linear
+const AMPLITUDE: f32 = 0.01;
+
+/// Returns the plugin's gain, in dB, for a sine at `freq` Hz, in both channels
+fn measure(mode: usize, gain_db: f32, freq: f32) -> f32 {
+    measure_resonant(mode, gain_db, 0., freq)
+}
+
+/// A plugin ready to process audio, with the cutoff at `CUTOFF`, and `resonance`, in `0..=1`
+fn plugin(mode: usize, gain_db: f32, resonance: f32) -> OnePoleFilter {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "mode", |_| mode as f32 / (NUM_MODES - 1) as f32);
+    // SAFETY: nothing else is touching the parameters
+    set_param(&plugin, "cutoff", |p| unsafe {
+        p.string_to_normalized_value(&format!("{CUTOFF} Hz"))
+            .unwrap()
+    });
+    set_param(&plugin, "gain", |_| (gain_db + 30.) / 60.);
+    set_param(&plugin, "resonance", |_| resonance);
+    plugin.prepare(SAMPLE_RATE);
+    plugin
+}
+
+/// [`measure`], with `resonance`, in `0..=1`
+fn measure_resonant(mode: usize, gain_db: f32, resonance: f32, freq: f32) -> f32 {
+    measure_plugin(plugin(mode, gain_db, resonance), freq)
+}
+
+/// [`measure`], with an already set up `plugin`
+fn measure_plugin(mut plugin: OnePoleFilter, freq: f32) -> f32 {
+    let w = 2. * PI * freq / SAMPLE_RATE;
+    let input: Vec<f32> = (0..NUM_SAMPLES)
+        .map(|i| AMPLITUDE * (w * i as f32).sin())
+        .collect();
+    let mut samples: Vec<[f32; 2]> = input.iter().map(|&x| [x, x]).collect();
+
+    process(&mut plugin, &mut samples);
+
+    let rms = |x: &[f32]| (x.iter().map(|s| s * s).sum::<f32>() / x.len() as f32).sqrt();
+    let settled = NUM_SAMPLES / 2..;
+    let input_rms = rms(&input[settled.clone()]);
+
+    let lane_db = |lane: usize| {
+        let output: Vec<f32> = samples[settled.clone()].iter().map(|s| s[lane]).collect();
+        util::gain_to_db(rms(&output) / input_rms)
+    };
+    let (l, r) = (lane_db(0), lane_db(1));
+    assert!((l - r).abs() < 1e-3, "channels differ: {l} dB vs {r} dB");
+    l
+}
+
+/// `tan(w / 2)`, where the bilinear transform maps the analog frequency `w`
+fn warp(freq: f32) -> f32 {
+    (PI * freq / SAMPLE_RATE).tan()
+}
+
+fn assert_response(mode: usize, gain_db: f32, expected_db: impl Fn(f32) -> f32) {
+    for freq in TEST_FREQS {
+        let measured = measure(mode, gain_db, freq);
+        let expected = expected_db(freq);
+        assert!(
+            (measured - expected).abs() < TOLERANCE_DB,
+            "mode {mode}, at {freq} Hz: measured {measured} dB, expected {expected} dB",
+        );
+    }
+}
+
+#[test]
+fn lowpass() {
+    assert_response(LOWPASS, 0., |f| {
+        let x = warp(f) / warp(CUTOFF);
+        util::gain_to_db(1. / (1. + x * x).sqrt())
+    });
+}
+
+#[test]
+fn lowpass_gain_as_output_level() {
+    // the default gain position, with no filter gain to set, sets the output level
+    assert_response(LOWPASS, 6., |f| {
+        let x = warp(f) / warp(CUTOFF);
+        util::gain_to_db(1. / (1. + x * x).sqrt()) + 6.
+    });
+}
+
+#[test]
+fn highpass() {
+    assert_response(HIGHPASS, 0., |f| {
+        let x = warp(f) / warp(CUTOFF);
+        util::gain_to_db(x / (1. + x * x).sqrt())
+    });
+}
+
+#[test]
+fn allpass() {
+    assert_response(ALLPASS, 0., |_| 0.);
+}
+
+#[test]
+fn tilt() {
+    // the lowpass and highpass outputs of a one-pole filter sum up to its input
+    assert_response(TILT, 0., |_| util::gain_to_db(0.5));
+}
+
+/// [`measure`], in the tilt EQ mode, with a slope of `slope_db`
+fn measure_tilt_eq(slope_db: f32, freq: f32) -> f32 {
+    let plugin = plugin(TILT_EQ, 0., 0.);
+    // glides there well before the measured half
+    automate(&plugin, "slope_db", (slope_db + 12.) / 24.);
+    measure_plugin(plugin, freq)
+}
+
+#[test]
+fn tilt_eq_pivot() {
+    for slope_db in [-12., -6., 3., 12.] {
+        let measured = measure_tilt_eq(slope_db, CUTOFF);
+        assert!(
+            measured.abs() < TOLERANCE_DB,
+            "slope {slope_db} dB: {measured} dB at the pivot",
+        );
+    }
+}
+
+#[test]
+fn tilt_eq_seesaw() {
+    // the lows go down as much as the highs go up, half the slope each
+    for slope_db in [-12., 12.] {
+        let (low, high) = (
+            measure_tilt_eq(slope_db, 20.),
+            measure_tilt_eq(slope_db, 18000.),
+        );
+        assert!(
+            (low + slope_db / 2.).abs() < 1.,
+            "slope {slope_db} dB: {low} dB at 20 Hz"
+        );
+        assert!(
+            (high - slope_db / 2.).abs() < 1.,
+            "slope {slope_db} dB: {high} dB at 18 kHz"
+        );
+    }
+}
+
+#[test]
+fn pivot_apart_from_cutoff() {
+    let pivot = 3000.;
+    let measure_at = |freq: f32| {
+        let plugin = plugin(TILT_EQ, 0., 0.);
+        automate(&plugin, "slope_db", 1.);
+        automate(&plugin, "pivot_follow", 0.);
+        // SAFETY: nothing else is touching the parameters
+        let normalized = unsafe {
+            find_param(&plugin, "pivot")
+                .string_to_normalized_value(&format!("{pivot} Hz"))
+                .unwrap()
+        };
+        automate(&plugin, "pivot", normalized);
+        measure_plugin(plugin, freq)
+    };
+    let at_pivot = measure_at(pivot);
+    assert!(at_pivot.abs() < TOLERANCE_DB, "{at_pivot} dB at the pivot");
+    // no longer the pivot, with the lows down
+    let at_cutoff = measure_at(CUTOFF);
+    assert!(at_cutoff < -1., "{at_cutoff} dB at the cutoff");
+}
+
+#[test]
+fn tilt_eq_flat_without_slope() {
+    assert_response(TILT_EQ, 0., |_| 0.);
+}
+
+#[test]
+fn shelves() {
+    // far enough from the cutoff, shelves are either at unity gain, or the set gain
+    let gain_db = 12.;
+    for (mode, low, high) in [(LOW_SHELF, gain_db, 0.), (HIGH_SHELF, 0., gain_db)] {
+        assert!((measure(mode, gain_db, 20.) - low).abs() < 1.);
+        assert!((measure(mode, gain_db, 18000.) - high).abs() < 1.);
+    }
+}
+
+#[test]
+fn shelves_flat_without_gain() {
+    for mode in [LOW_SHELF, HIGH_SHELF] {
+        assert_response(mode, 0., |_| 0.);
+    }
+}
+
+#[test]
+fn highpass_damping() {
+    for freq in TEST_FREQS {
+        let damped = plugin(HIGHPASS, 0., 0.);
+        set_param(&damped, "damping", |_| 1.);
+        let added = measure_plugin(damped, freq) - measure(HIGHPASS, 0., freq);
+
+        let x = warp(freq) / warp(DAMPING_FREQ);
+        let expected = util::gain_to_db(1. / (1. + x * x).sqrt());
+        assert!(
+            (added - expected).abs() < TOLERANCE_DB,
+            "at {freq} Hz: damping changed the gain by {added} dB, expected {expected} dB",
+        );
+    }
+}
+
+#[test]
+fn resonance_keeps_passband() {
+    // well below the cutoff, and the resonant peak, above it
+    for freq in [20., 100.] {
+        let flat = measure(LOWPASS, 0., freq);
+        for resonance in [0.25, 0.5, 1.] {
+            let resonant = measure_resonant(LOWPASS, 0., resonance, freq);
+            assert!(
+                (resonant - flat).abs() < TOLERANCE_DB,
+                "at {freq} Hz, resonance {resonance}: {resonant} dB, {flat} dB without",
+            );
+        }
+    }
+}
+
+#[test]
+fn computed_response_matches_measured() {
+    for mode in 0..NUM_MODES {
+        for resonance in [0., 0.5] {
+            let computed = plugin(mode, 6., resonance).magnitude_response(&TEST_FREQS);
+            for (freq, gain) in TEST_FREQS.into_iter().zip(computed) {
+                let measured = measure_resonant(mode, 6., resonance, freq);
+                let computed = util::gain_to_db(gain);
+                assert!(
+                    (measured - computed).abs() < TOLERANCE_DB,
+                    "mode {mode}, resonance {resonance}, at {freq} Hz: \
+                    measured {measured} dB, computed {computed} dB",
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn response_curve_spans_the_cutoff_range() {
+    const NUM_FREQS: usize = 64;
+    let plugin = plugin(LOWPASS, 0., 0.5);
+    let curve = plugin.response_curve(NUM_FREQS, true);
+
+    assert_eq!(curve.freqs.len(), NUM_FREQS);
+    // the cutoff knob's range, `MIN_FREQ..MAX_FREQ`
+    assert!((curve.freqs[0] - 13.).abs() < 1e-3);
+    assert!((curve.freqs[NUM_FREQS - 1] - 21000.).abs() < 1.);
+    assert!(curve.freqs.windows(2).all(|f| f[0] < f[1]));
+
+    assert_eq!(curve.magnitudes, plugin.magnitude_response(&curve.freqs));
+    assert_eq!(curve.phases.map(|phases| phases.len()), Some(NUM_FREQS));
+}
+
+#[test]
+fn allpass_curve_is_flat() {
+    let curve = plugin(ALLPASS, 0., 0.).response_curve(32, false);
+    assert!(curve.phases.is_none());
+    for (freq, gain) in curve.freqs.iter().zip(curve.magnitudes) {
+        let gain = util::gain_to_db(gain);
+        assert!(gain.abs() < 1e-3, "{gain} dB at {freq} Hz");
+    }
+}
+
+#[test]
+fn allpass_is_flat_with_every_character() {
+    // far up, where the analog character's lowpass departs from the bilinear one
+    let cutoff = 8000.;
+    let freqs = [100., 1000., 8000., 15000., 20000.];
+    for character in [0., 0.5, 1.] {
+        let plugin = || {
+            let mut plugin = plugin(ALLPASS, 0., 0.);
+            set_param(&plugin, "character", |_| character);
+            // SAFETY: nothing else is touching the parameters
+            set_param(&plugin, "cutoff", |p| unsafe {
+                p.string_to_normalized_value(&format!("{cutoff} Hz"))
+                    .unwrap()
+            });
+            // set up again, for the new character and cutoff
+            plugin.prepare(SAMPLE_RATE);
+            plugin
+        };
+        let computed = plugin().magnitude_response(&freqs);
+        for (freq, gain) in freqs.into_iter().zip(computed) {
+            let computed = util::gain_to_db(gain);
+            assert!(
+                computed.abs() < 1e-3,
+                "character {character}, at {freq} Hz: computed {computed} dB",
+            );
+            let measured = measure_plugin(plugin(), freq);
+            assert!(
+                measured.abs() < TOLERANCE_DB,
+                "character {character}, at {freq} Hz: measured {measured} dB",
+            );
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! The Haas delay holds the right channel back, after the filters
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{automate, process, set_param, SAMPLE_RATE};
+
+const NUM_SAMPLES: usize = 4096;
+
+fn plugin(delay_ms: f32) -> OnePoleFilter {
+    let mut plugin = OnePoleFilter::default();
+    // out of `0..=30` ms
+    set_param(&plugin, "haas", |_| delay_ms / 30.);
+    plugin.prepare(SAMPLE_RATE);
+    plugin
+}
+
+#[test]
+fn delays_the_right_channel() {
+    let mut plugin = plugin(10.);
+    let mut samples = vec![[0.; 2]; NUM_SAMPLES];
+    samples[0] = [1.; 2];
+    process(&mut plugin, &mut samples);
+
+    // 10 ms, at 44.1 kHz
+    let delay = 441;
+    for i in 0..NUM_SAMPLES - delay {
+        assert_eq!(samples[i][0], samples[i + delay][1], "sample {i}");
+    }
+}
+
+#[test]
+fn no_delay_by_default() {
+    let mut plugin = plugin(0.);
+    let mut samples: Vec<[f32; 2]> = (0..NUM_SAMPLES)
+        .map(|i| [(i as f32 * 0.1).sin(); 2])
+        .collect();
+    process(&mut plugin, &mut samples);
+    assert!(samples.iter().all(|[l, r]| l == r));
+}
+
+#[test]
+fn glides_without_clicking() {
+    let mut plugin = plugin(0.);
+    let w = 2. * core::f32::consts::PI * 200. / SAMPLE_RATE;
+    let mut samples: Vec<[f32; 2]> = (0..NUM_SAMPLES)
+        .map(|i| [0.5 * (w * i as f32).sin(); 2])
+        .collect();
+    let (before, after) = samples.split_at_mut(NUM_SAMPLES / 2);
+    process(&mut plugin, before);
+    automate(&plugin, "haas", 1.);
+    process(&mut plugin, after);
+
+    // the sine's steepest slope, doubled, for the pitch rising as the delay grows
+    let limit = 2. * 0.5 * w;
+    let step = samples
+        .windows(2)
+        .map(|w| (w[1][1] - w[0][1]).abs())
+        .fold(0., f32::max);
+    assert!(step <= limit, "jumped by {step}, more than {limit}");
+}
+
+/// An impulse in each of `num_channels` channels, processed by `plugin`
+fn impulses(plugin: &mut OnePoleFilter, num_channels: usize) -> Vec<Vec<f32>> {
+    let mut channels = vec![vec![0.; NUM_SAMPLES]; num_channels];
+    for channel in &mut channels {
+        channel[0] = 1.;
+    }
+    let mut slices: Vec<&mut [f32]> = channels.iter_mut().map(Vec::as_mut_slice).collect();
+    plugin.process_channels(&mut slices);
+    channels
+}
+
+#[test]
+fn only_delays_the_front_pair() {
+    let output = impulses(&mut plugin(10.), 4);
+    assert_ne!(output[0], output[1]);
+    assert_eq!(output[2], output[3]);
+    assert_eq!(output[0], output[2]);
+}
+
+#[test]
+fn the_tail_only_counts_the_delay_with_a_front_pair() {
+    let mut plugin = plugin(10.);
+    impulses(&mut plugin, 1);
+    assert_eq!(plugin.remaining_tail(0.), Some(0));
+    impulses(&mut plugin, 2);
+    assert_eq!(plugin.remaining_tail(0.), Some(441));
+}
@@ -0,0 +1,69 @@
+//! With resonance headroom on, the output is turned down as the resonance rises,
+//! keeping its peak at, or below, the passband's level
+
+use core::f32::consts::PI;
+
+use nih_plug::prelude::*;
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{process, set_param, SAMPLE_RATE};
+
+/// Index of each mode in the `mode` parameter, out of 6
+const LOWPASS: f32 = 0.;
+const HIGHPASS: f32 = 0.2;
+
+const NUM_SAMPLES: usize = SAMPLE_RATE as usize / 10;
+
+fn plugin(mode: f32, cutoff: f32, headroom: bool) -> OnePoleFilter {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "mode", |_| mode);
+    for id in ["cutoff", "cutoff_r"] {
+        // SAFETY: nothing else is touching the parameters
+        set_param(&plugin, id, |p| unsafe {
+            p.string_to_normalized_value(&format!("{cutoff} Hz"))
+                .unwrap()
+        });
+    }
+    set_param(&plugin, "resonance", |_| 1.);
+    set_param(&plugin, "res_headroom", |_| if headroom { 1. } else { 0. });
+    plugin.prepare(SAMPLE_RATE);
+    plugin
+}
+
+/// Highest gain of the plugin's response, in dB
+fn peak_db(plugin: &OnePoleFilter) -> f32 {
+    let curve = plugin.response_curve(512, false);
+    util::gain_to_db(curve.magnitudes.into_iter().fold(0., f32::max))
+}
+
+#[test]
+fn the_peak_stays_under_the_passband() {
+    for mode in [LOWPASS, HIGHPASS] {
+        for cutoff in [100., 1000., 10000.] {
+            let peak = peak_db(&plugin(mode, cutoff, true));
+            assert!(peak < 0.01, "peaks at {peak} dB, cutoff at {cutoff} Hz");
+        }
+    }
+
+    // without it, the highpass' peak, near Nyquist, is way above
+    assert!(peak_db(&plugin(HIGHPASS, 1000., false)) > 12.);
+}
+
+#[test]
+fn turns_the_output_down() {
+    let rms = |headroom| {
+        let mut plugin = plugin(HIGHPASS, 1000., headroom);
+        let w = 2. * PI * 5000. / SAMPLE_RATE;
+        let mut samples: Vec<[f32; 2]> = (0..NUM_SAMPLES)
+            .map(|i| [0.01 * (w * i as f32).sin(); 2])
+            .collect();
+        process(&mut plugin, &mut samples);
+        let settled = &samples[NUM_SAMPLES / 2..];
+        (settled.iter().map(|[l, _]| l * l).sum::<f32>() / settled.len() as f32).sqrt()
+    };
+
+    // `1 - 0.9`, at full resonance
+    let change = util::gain_to_db(rms(true) / rms(false));
+    assert!((change + 20.).abs() < 0.1, "{change} dB");
+}
@@ -0,0 +1,68 @@
+//! Two instances, one lowpass, one highpass, at the same cutoff, run in parallel,
+//! must add back up to their input, in phase, for stacking them as a multiband.
+//! That's only the case with a single stage, and no resonance, the defaults
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{process, set_param, SAMPLE_RATE};
+
+const NUM_SAMPLES: usize = 8192;
+
+/// Residual level, relative to the input's
+const RECONSTRUCTION_FLOOR_DB: f32 = -100.;
+
+/// Normalized values of the mode parameter
+const LOWPASS: f32 = 0.;
+const HIGHPASS: f32 = 1. / 6.;
+
+/// Normalized values of the character parameter, clean, analog, and clean (64-bit)
+const CHARACTERS: [f32; 3] = [0., 0.5, 1.];
+
+fn input() -> Vec<[f32; 2]> {
+    let mut seed = 1u32;
+    (0..NUM_SAMPLES)
+        .map(|_| {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            let x = seed as f32 / u32::MAX as f32 - 0.5;
+            [x, -x]
+        })
+        .collect()
+}
+
+fn render(mode: f32, character: f32, cutoff: f32) -> Vec<[f32; 2]> {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "mode", |_| mode);
+    set_param(&plugin, "character", |_| character);
+    set_param(&plugin, "cutoff", |_| cutoff);
+    plugin.prepare(SAMPLE_RATE);
+
+    let mut samples = input();
+    process(&mut plugin, &mut samples);
+    samples
+}
+
+#[test]
+fn lowpass_and_highpass_sum_to_the_input() {
+    let input = input();
+    let energy = |x: &mut dyn Iterator<Item = f32>| x.map(|s| s * s).sum::<f32>();
+    let input_energy = energy(&mut input.iter().flatten().copied());
+
+    for character in CHARACTERS {
+        for cutoff in [0.2, 0.5, 0.8] {
+            let (low, high) = (
+                render(LOWPASS, character, cutoff),
+                render(HIGHPASS, character, cutoff),
+            );
+            let mut residual = input
+                .iter()
+                .zip(low.iter().zip(&high))
+                .flat_map(|(x, (l, h))| (0..2).map(move |lane| l[lane] + h[lane] - x[lane]));
+            let error_db = 10. * (energy(&mut residual) / input_energy).log10();
+            assert!(
+                error_db < RECONSTRUCTION_FLOOR_DB,
+                "character {character}, cutoff {cutoff}: residual at {error_db} dB",
+            );
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! A NaN, or infinite, input sample is replaced with silence, before it's
+//! split into the dry and wet signals, so that it can't reach the output, or
+//! leave the plugin stuck, through either of them
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{set_param, BLOCK_SIZE, SAMPLE_RATE};
+
+/// Renders a sine, broken by non-finite samples over its first block, with
+/// `configure`'s settings, and returns the next block
+fn block_after_nan(configure: impl FnOnce(&OnePoleFilter)) -> Vec<[f32; 2]> {
+    let mut plugin = OnePoleFilter::default();
+    configure(&plugin);
+    plugin.prepare(SAMPLE_RATE);
+
+    let w = 2. * core::f32::consts::PI * 1000. / SAMPLE_RATE;
+    let mut samples: Vec<[f32; 2]> = (0..2 * BLOCK_SIZE)
+        .map(|i| [0.5 * (w * i as f32).sin(); 2])
+        .collect();
+    samples[100] = [f32::NAN, 0.];
+    samples[200] = [f32::INFINITY, f32::NEG_INFINITY];
+
+    let (first, next) = samples.split_at_mut(BLOCK_SIZE);
+    plugin.process_block(first);
+    plugin.process_block(next);
+    next.to_vec()
+}
+
+fn assert_finite_and_audible(block: &[[f32; 2]], settings: &str) {
+    for (i, sample) in block.iter().enumerate() {
+        assert!(
+            sample.iter().all(|x| x.is_finite()),
+            "{settings}, sample {i}: {sample:?}",
+        );
+    }
+    let peak = block
+        .iter()
+        .flatten()
+        .fold(0., |peak: f32, x| peak.max(x.abs()));
+    assert!(peak > 0.01, "{settings}: silent, {peak} at most");
+}
+
+#[test]
+fn wet() {
+    assert_finite_and_audible(&block_after_nan(|_| ()), "fully wet");
+}
+
+#[test]
+fn mixed() {
+    let block = block_after_nan(|plugin| set_param(plugin, "mix", |_| 0.5));
+    assert_finite_and_audible(&block, "half wet");
+}
+
+#[test]
+fn bypassed() {
+    let block = block_after_nan(|plugin| set_param(plugin, "bypass", |_| 1.));
+    assert_finite_and_audible(&block, "bypassed");
+}
@@ -0,0 +1,76 @@
+//! Settings meant to leave the signal alone must null against the input, to
+//! within rounding. Checked with `OnePoleFilter::null_test`
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{set_param, SAMPLE_RATE};
+
+const NUM_SAMPLES: usize = 8192;
+
+/// Anything above this is audible, or at least shouldn't be there
+const NULL_FLOOR_DB: f32 = -120.;
+
+/// Residual peak level, in dBFS, with `configure`'s settings
+fn null_floor(configure: impl FnOnce(&OnePoleFilter)) -> f32 {
+    let mut plugin = OnePoleFilter::default();
+    // something to remove, were it processed
+    set_param(&plugin, "mode", |_| 0.2);
+    set_param(&plugin, "resonance", |_| 0.5);
+    set_param(&plugin, "drive", |_| 0.5);
+    configure(&plugin);
+    plugin.prepare(SAMPLE_RATE);
+
+    let mut seed = 1u32;
+    let mut samples: Vec<[f32; 2]> = (0..NUM_SAMPLES)
+        .map(|_| {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            let x = seed as f32 / u32::MAX as f32 - 0.5;
+            [x, -x]
+        })
+        .collect();
+
+    let gains = plugin.null_test(&mut samples);
+    for gain in gains {
+        assert!(
+            (gain - 1.).abs() < 1e-6,
+            "level changed, by a factor of {gain}"
+        );
+    }
+
+    let peak = samples
+        .iter()
+        .flatten()
+        .fold(0f32, |peak, x| peak.max(x.abs()));
+    20. * peak.max(f32::MIN_POSITIVE).log10()
+}
+
+#[test]
+fn bypass_nulls() {
+    let floor = null_floor(|plugin| set_param(plugin, "bypass", |_| 1.));
+    assert!(floor < NULL_FLOOR_DB, "null floor at {floor} dBFS");
+}
+
+#[test]
+fn dry_mix_nulls() {
+    let floor = null_floor(|plugin| set_param(plugin, "mix", |_| 0.));
+    assert!(floor < NULL_FLOOR_DB, "null floor at {floor} dBFS");
+}
+
+#[test]
+fn dry_mix_nulls_when_oversampling() {
+    let floor = null_floor(|plugin| {
+        set_param(plugin, "mix", |_| 0.);
+        set_param(plugin, "oversampling", |_| 1.);
+    });
+    assert!(floor < NULL_FLOOR_DB, "null floor at {floor} dBFS");
+}
+
+#[test]
+fn dry_mix_nulls_with_true_peak_limiting() {
+    let floor = null_floor(|plugin| {
+        set_param(plugin, "mix", |_| 0.);
+        set_param(plugin, "ceiling_mode", |_| 1.);
+    });
+    assert!(floor < NULL_FLOOR_DB, "null floor at {floor} dBFS");
+}
@@ -0,0 +1,96 @@
+//! Compares the single and double precision versions of the clean filter to a
+//! reference computed in double precision, for 4 lowpass stages at the lowest cutoff,
+//! where rounding errors in the filters' state are the largest
+
+use core::f64::consts::{PI, TAU};
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{process, set_param, SAMPLE_RATE};
+
+/// Index of each filter topology in the `character` parameter
+const CLEAN: usize = 0;
+const PRECISE: usize = 2;
+const NUM_CHARACTERS: usize = 3;
+
+const NUM_STAGES: i32 = 4;
+/// `MIN_FREQ`, where the cutoff knob's range starts
+const CUTOFF: f64 = 13.;
+/// ten seconds
+const NUM_SAMPLES: usize = 10 * SAMPLE_RATE as usize;
+
+/// Exponential sine sweep, from 10 Hz to 1 kHz, rounded to single precision, as
+/// that's what the plugin gets
+fn sweep() -> Vec<f64> {
+    let (start, end) = (10f64, 1000f64);
+    let duration = NUM_SAMPLES as f64 / f64::from(SAMPLE_RATE);
+    let rate = (end / start).ln() / duration;
+    (0..NUM_SAMPLES)
+        .map(|i| {
+            let t = i as f64 / f64::from(SAMPLE_RATE);
+            let x = 0.5 * (TAU * start * ((rate * t).exp() - 1.) / rate).sin();
+            f64::from(x as f32)
+        })
+        .collect()
+}
+
+/// `input` through `NUM_STAGES` bilinear one-pole lowpasses, with
+/// their cutoffs raised for the cascade to be 3 dB down at `CUTOFF`
+fn reference(input: &[f64]) -> Vec<f64> {
+    let compensation = ((f64::from(NUM_STAGES)).recip().exp2() - 1.).sqrt();
+    let g = (PI * CUTOFF / compensation / f64::from(SAMPLE_RATE)).tan();
+    let k = g / (1. + g);
+
+    let mut states = [0.; NUM_STAGES as usize];
+    input
+        .iter()
+        .map(|&x| {
+            states.iter_mut().fold(x, |x, s| {
+                let v = k * (x - *s);
+                let y = v + *s;
+                *s = y + v;
+                y
+            })
+        })
+        .collect()
+}
+
+/// RMS of the difference between the plugin's output and the reference,
+/// relative to the reference's RMS
+fn relative_error(character: usize, input: &[f64], expected: &[f64]) -> f64 {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "character", |_| {
+        character as f32 / (NUM_CHARACTERS - 1) as f32
+    });
+    set_param(&plugin, "cutoff", |_| 0.);
+    // the steepest slope, `NUM_STAGES` stages
+    set_param(&plugin, "slope", |_| 1.);
+    plugin.prepare(SAMPLE_RATE);
+
+    let mut samples: Vec<[f32; 2]> = input.iter().map(|&x| [x as f32; 2]).collect();
+    process(&mut plugin, &mut samples);
+
+    let (error, power) =
+        samples
+            .iter()
+            .zip(expected)
+            .fold((0., 0.), |(error, power), (out, &expected)| {
+                let diff = f64::from(out[0]) - expected;
+                (error + diff * diff, power + expected * expected)
+            });
+    (error / power).sqrt()
+}
+
+#[test]
+fn double_precision_is_more_accurate() {
+    let input = sweep();
+    let expected = reference(&input);
+
+    let single = relative_error(CLEAN, &input, &expected);
+    let double = relative_error(PRECISE, &input, &expected);
+    assert!(
+        double < single,
+        "double precision error: {double}, single precision error: {single}",
+    );
+}
@@ -0,0 +1,36 @@
+//! With static settings, and no oversampling, the eco quality only skips work
+//! that isn't needed, and must sound the same as the standard one
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{process, set_param, SAMPLE_RATE};
+
+const NUM_SAMPLES: usize = 4096;
+
+/// Normalized values of the quality parameter
+const ECO: f32 = 0.;
+const STANDARD: f32 = 0.5;
+
+fn render(quality: f32) -> Vec<[f32; 2]> {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "quality", |_| quality);
+    set_param(&plugin, "resonance", |_| 0.5);
+    plugin.prepare(SAMPLE_RATE);
+
+    let mut seed = 1u32;
+    let mut samples: Vec<[f32; 2]> = (0..NUM_SAMPLES)
+        .map(|_| {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            let x = seed as f32 / u32::MAX as f32 - 0.5;
+            [x, -x]
+        })
+        .collect();
+    process(&mut plugin, &mut samples);
+    samples
+}
+
+#[test]
+fn eco_matches_standard_when_static() {
+    assert_eq!(render(ECO), render(STANDARD));
+}
@@ -0,0 +1,65 @@
+//! Resets a plugin halfway through a cutoff, gain and resonance automation. Its
+//! smoothers must be left where they were heading, without ramping any further,
+//! so it must then sound just like a plugin freshly set up with those values
+
+use nih_plug::prelude::*;
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{automate, process, set_param, SAMPLE_RATE};
+
+const NUM_SAMPLES: usize = 4096;
+
+/// Normalized values, before and after the automation
+const CUTOFFS: [f32; 2] = [0.2, 0.8];
+const GAINS: [f32; 2] = [0.5, 0.9];
+const RESONANCES: [f32; 2] = [0., 0.6];
+
+fn noise() -> Vec<[f32; 2]> {
+    let mut seed = 1u32;
+    (0..NUM_SAMPLES)
+        .map(|_| {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            let x = seed as f32 / u32::MAX as f32 - 0.5;
+            [x, -x]
+        })
+        .collect()
+}
+
+#[test]
+fn no_ramp_after_reset() {
+    let mut fresh = OnePoleFilter::default();
+    set_param(&fresh, "cutoff", |_| CUTOFFS[1]);
+    set_param(&fresh, "gain", |_| GAINS[1]);
+    set_param(&fresh, "resonance", |_| RESONANCES[1]);
+    fresh.prepare(SAMPLE_RATE);
+
+    let mut reset = OnePoleFilter::default();
+    set_param(&reset, "cutoff", |_| CUTOFFS[0]);
+    set_param(&reset, "gain", |_| GAINS[0]);
+    set_param(&reset, "resonance", |_| RESONANCES[0]);
+    reset.prepare(SAMPLE_RATE);
+    automate(&reset, "cutoff", CUTOFFS[1]);
+    automate(&reset, "gain", GAINS[1]);
+    automate(&reset, "resonance", RESONANCES[1]);
+    // a few milliseconds, the smoothers are still on their way
+    process(&mut reset, &mut noise()[..128]);
+    // not playing, so it's cleared right away
+    reset.reset();
+
+    let mut expected = noise();
+    process(&mut fresh, &mut expected);
+    let mut output = noise();
+    process(&mut reset, &mut output);
+
+    for (i, (a, b)) in output.iter().zip(&expected).enumerate() {
+        for lane in 0..2 {
+            assert!(
+                (a[lane] - b[lane]).abs() < 1e-6,
+                "sample {i}, lane {lane}: {} after a reset, {} when fresh",
+                a[lane],
+                b[lane],
+            );
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Automates the resonance back and forth, every block, and checks that the output
+//! doesn't move any faster than it does with the resonance left alone
+
+use core::f32::consts::TAU;
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{automate, set_param, BLOCK_SIZE, SAMPLE_RATE};
+
+/// Highpass, whose output follows its input's jumps, unlike the lowpass'
+const HIGHPASS: f32 = 0.2;
+const FREQ: f32 = 2000.;
+/// Quiet enough for the resonance loop's soft clipper to stay linear
+const AMPLITUDE: f32 = 0.01;
+const NUM_BLOCKS: usize = 64;
+
+/// Largest difference between consecutive output samples. `resonance` gives the
+/// resonance, in `0..=1`, for each block
+fn max_step(resonance: impl Fn(usize) -> f32) -> f32 {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "mode", |_| HIGHPASS);
+    set_param(&plugin, "resonance", |_| resonance(0));
+    plugin.prepare(SAMPLE_RATE);
+
+    let w = TAU * FREQ / SAMPLE_RATE;
+    let mut samples: Vec<[f32; 2]> = (0..NUM_BLOCKS * BLOCK_SIZE)
+        .map(|i| [AMPLITUDE * (w * i as f32).sin(); 2])
+        .collect();
+
+    for (i, block) in samples.chunks_mut(BLOCK_SIZE).enumerate() {
+        automate(&plugin, "resonance", resonance(i));
+        plugin.process_block(block);
+    }
+
+    samples
+        .windows(2)
+        .map(|pair| (pair[1][0] - pair[0][0]).abs())
+        .fold(0., f32::max)
+}
+
+#[test]
+fn automated_resonance_has_no_discontinuities() {
+    let steady = max_step(|_| 0.).max(max_step(|_| 1.));
+    let automated = max_step(|block| (block % 2) as f32);
+    assert!(
+        automated < 1.25 * steady,
+        "largest step: {automated} when automated, {steady} otherwise",
+    );
+}
@@ -0,0 +1,58 @@
+//! Feeds sines through the filter, with the rumble filter on and off. Its 20 Hz
+//! highpass must bring 10 Hz down by 7 dB, and leave 100 Hz nearly untouched
+
+use core::f32::consts::TAU;
+
+use nih_plug::prelude::*;
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{process, set_param, SAMPLE_RATE};
+
+/// two seconds, the second one is measured, once the highpass has settled,
+/// an exact number of periods of both test frequencies
+const NUM_SAMPLES: usize = 2 * SAMPLE_RATE as usize;
+const TOLERANCE_DB: f32 = 0.05;
+
+/// Level of the left channel's output, for a sine at `freq` Hz, in dB
+fn level(rumble: bool, freq: f32) -> f32 {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "rumble", |_| if rumble { 1. } else { 0. });
+    plugin.prepare(SAMPLE_RATE);
+
+    let mut samples: Vec<[f32; 2]> = (0..NUM_SAMPLES)
+        .map(|i| [0.5 * (TAU * freq * i as f32 / SAMPLE_RATE).sin(); 2])
+        .collect();
+    process(&mut plugin, &mut samples);
+
+    let settled = &samples[NUM_SAMPLES / 2..];
+    let power = settled.iter().map(|s| s[0] * s[0]).sum::<f32>() / settled.len() as f32;
+    util::gain_to_db(power.sqrt())
+}
+
+/// How much the rumble filter changes the level of a sine at `freq` Hz, in dB
+fn attenuation(freq: f32) -> f32 {
+    level(true, freq) - level(false, freq)
+}
+
+#[test]
+fn attenuates_subsonics() {
+    // a one-pole highpass, an octave below its cutoff: 1 / sqrt(1 + 2²)
+    let expected = -10. * 5f32.log10();
+    let measured = attenuation(10.);
+    assert!(
+        (measured - expected).abs() < TOLERANCE_DB,
+        "10 Hz changed by {measured} dB, expected {expected} dB",
+    );
+}
+
+#[test]
+fn leaves_the_audible_range_alone() {
+    // a decade above its cutoff: 1 / sqrt(1 + 0.2²)
+    let expected = -10. * 1.04f32.log10();
+    let measured = attenuation(100.);
+    assert!(
+        (measured - expected).abs() < TOLERANCE_DB,
+        "100 Hz changed by {measured} dB, expected {expected} dB",
+    );
+}
@@ -0,0 +1,35 @@
+//! A corrupt saved state mustn't leave parameters out of their ranges,
+//! or the plugin putting out garbage
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{find_param, process, set_param, SAMPLE_RATE};
+
+#[test]
+fn malformed_state_recovers() {
+    let mut plugin = OnePoleFilter::default();
+    // as a restored state could have them
+    set_param(&plugin, "cutoff", |_| f32::NAN);
+    set_param(&plugin, "resonance", |_| 5.);
+    set_param(&plugin, "gain", |_| -3.);
+    plugin.prepare(SAMPLE_RATE);
+
+    // SAFETY: nothing else is touching the parameters
+    let normalized = |id| unsafe { find_param(&plugin, id).unmodulated_normalized_value() };
+    let cutoff = find_param(&plugin, "cutoff");
+    assert_eq!(normalized("cutoff"), unsafe {
+        cutoff.default_normalized_value()
+    });
+    assert_eq!(normalized("resonance"), 1.);
+    assert_eq!(normalized("gain"), 0.);
+
+    let mut samples: Vec<[f32; 2]> = (0..4096)
+        .map(|i| {
+            let x = (i as f32 * 0.1).sin();
+            [x, -x]
+        })
+        .collect();
+    process(&mut plugin, &mut samples);
+    assert!(samples.iter().flatten().all(|x| x.is_finite()));
+}
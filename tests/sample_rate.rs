@@ -0,0 +1,84 @@
+//! The cutoff is set in Hz, it must stay put whatever the sample rate, including
+//! when the host changes it without initializing the plugin again
+
+use core::f32::consts::PI;
+
+use nih_plug::prelude::*;
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{process_at, set_param, SAMPLE_RATE};
+
+const CUTOFF: f32 = 1000.;
+const HIGH_SAMPLE_RATE: f32 = 96000.;
+/// a single lowpass' gain at its cutoff, in dB
+const CUTOFF_DB: f32 = -3.0103;
+const TOLERANCE_DB: f32 = 0.1;
+/// Quiet enough for the resonance loop's soft clipper to stay linear
+const AMPLITUDE: f32 = 0.01;
+
+/// A lowpass, at `CUTOFF`, prepared at `SAMPLE_RATE`
+fn plugin() -> OnePoleFilter {
+    let mut plugin = OnePoleFilter::default();
+    // SAFETY: nothing else is touching the parameters
+    set_param(&plugin, "cutoff", |p| unsafe {
+        p.string_to_normalized_value(&format!("{CUTOFF} Hz"))
+            .unwrap()
+    });
+    plugin.prepare(SAMPLE_RATE);
+    plugin
+}
+
+/// The plugin's gain, in dB, for a sine at `freq` Hz, with the host at
+/// `sample_rate`, over the second half of a second of it
+fn measure(plugin: &mut OnePoleFilter, freq: f32, sample_rate: f32) -> f32 {
+    let num_samples = sample_rate as usize;
+    let w = 2. * PI * freq / sample_rate;
+    let input: Vec<f32> = (0..num_samples)
+        .map(|i| AMPLITUDE * (w * i as f32).sin())
+        .collect();
+    let mut samples: Vec<[f32; 2]> = input.iter().map(|&x| [x, x]).collect();
+
+    process_at(plugin, &mut samples, sample_rate);
+
+    let rms = |x: &mut dyn Iterator<Item = f32>, n: usize| {
+        (x.map(|s| s * s).sum::<f32>() / n as f32).sqrt()
+    };
+    let settled = num_samples / 2;
+    let n = num_samples - settled;
+    let input_rms = rms(&mut input[settled..].iter().copied(), n);
+    let output_rms = rms(&mut samples[settled..].iter().map(|s| s[0]), n);
+    util::gain_to_db(output_rms / input_rms)
+}
+
+fn assert_at_cutoff(measured: f32, sample_rate: f32) {
+    assert!(
+        (measured - CUTOFF_DB).abs() < TOLERANCE_DB,
+        "at {sample_rate} Hz: {measured} dB at the cutoff, expected {CUTOFF_DB} dB",
+    );
+}
+
+#[test]
+fn cutoff_in_hz_at_any_rate() {
+    for sample_rate in [SAMPLE_RATE, HIGH_SAMPLE_RATE] {
+        let mut plugin = plugin();
+        if sample_rate != SAMPLE_RATE {
+            // prepared again, for the new rate
+            plugin.prepare(sample_rate);
+        }
+        assert_at_cutoff(measure(&mut plugin, CUTOFF, sample_rate), sample_rate);
+    }
+}
+
+#[test]
+fn follows_rate_changes_without_reinitializing() {
+    let mut plugin = plugin();
+    assert_at_cutoff(measure(&mut plugin, CUTOFF, SAMPLE_RATE), SAMPLE_RATE);
+    // the host switches rates mid-run
+    assert_at_cutoff(
+        measure(&mut plugin, CUTOFF, HIGH_SAMPLE_RATE),
+        HIGH_SAMPLE_RATE,
+    );
+    // and back
+    assert_at_cutoff(measure(&mut plugin, CUTOFF, SAMPLE_RATE), SAMPLE_RATE);
+}
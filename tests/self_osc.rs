@@ -0,0 +1,72 @@
+//! With self-oscillation on, the filter rings at the cutoff, without any input
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{process, set_param, SAMPLE_RATE};
+
+/// long enough to count plenty of cycles, the first tenth is skipped
+const NUM_SAMPLES: usize = SAMPLE_RATE as usize;
+/// relative
+const TOLERANCE: f32 = 0.005;
+
+/// Index of the all-pass mode, out of 7, so that the sine's level doesn't
+/// depend on the cutoff
+const ALLPASS: f32 = 2. / 6.;
+
+/// Normalized values of the `oversampling` parameter
+const NO_OVERSAMPLING: f32 = 0.;
+const OVERSAMPLING_4X: f32 = 1.;
+
+/// Renders silence through a self-oscillating plugin, returns the left channel
+fn render(cutoff: f32, resonance: f32, oversampling: f32) -> Vec<f32> {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "self_osc", |_| 1.);
+    set_param(&plugin, "mode", |_| ALLPASS);
+    for id in ["cutoff", "cutoff_r"] {
+        // SAFETY: nothing else is touching the parameters
+        set_param(&plugin, id, |p| unsafe {
+            p.string_to_normalized_value(&format!("{cutoff} Hz"))
+                .unwrap()
+        });
+    }
+    set_param(&plugin, "resonance", |_| resonance);
+    set_param(&plugin, "oversampling", |_| oversampling);
+    plugin.prepare(SAMPLE_RATE);
+
+    let mut samples = vec![[0.; 2]; NUM_SAMPLES];
+    process(&mut plugin, &mut samples);
+    samples[NUM_SAMPLES / 10..]
+        .iter()
+        .map(|[l, _]| *l)
+        .collect()
+}
+
+/// From the (linearly interpolated) times of the first and last upwards zero crossings
+fn measure_freq(samples: &[f32]) -> f32 {
+    let crossings: Vec<f32> = samples
+        .windows(2)
+        .enumerate()
+        .filter(|(_, w)| w[0] < 0. && w[1] >= 0.)
+        .map(|(i, w)| i as f32 + w[0] / (w[0] - w[1]))
+        .collect();
+    let (first, last) = (crossings[0], crossings[crossings.len() - 1]);
+    (crossings.len() - 1) as f32 * SAMPLE_RATE / (last - first)
+}
+
+#[test]
+fn oscillates_at_the_cutoff() {
+    for oversampling in [NO_OVERSAMPLING, OVERSAMPLING_4X] {
+        for cutoff in [100., 1000., 5000.] {
+            let freq = measure_freq(&render(cutoff, 1., oversampling));
+            let error = (freq / cutoff - 1.).abs();
+            assert!(error < TOLERANCE, "{freq} Hz, for a cutoff of {cutoff} Hz");
+        }
+    }
+}
+
+#[test]
+fn silent_with_little_resonance() {
+    let samples = render(1000., 0.4, NO_OVERSAMPLING);
+    assert!(samples.iter().all(|&x| x == 0.));
+}
@@ -0,0 +1,87 @@
+//! Bypassed stages are skipped altogether, a cascade with some of its stages
+//! bypassed must sound the same as a shorter one. Their smoothers don't run
+//! either, so that turning them back on, after the cutoff moved, doesn't click
+
+use core::f32::consts::PI;
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{automate, process, set_param, BLOCK_SIZE, SAMPLE_RATE};
+
+const NUM_SAMPLES: usize = 4096;
+const MAX_STAGES: usize = 4;
+
+/// Renders noise through the first `num_stages` stages, minus those in `bypassed`,
+/// counting from 1
+fn render(num_stages: usize, bypassed: &[usize]) -> Vec<[f32; 2]> {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "slope", |_| {
+        (num_stages - 1) as f32 / (MAX_STAGES - 1) as f32
+    });
+    for stage in bypassed {
+        set_param(&plugin, &format!("on_{stage}"), |_| 0.);
+    }
+    set_param(&plugin, "resonance", |_| 0.5);
+    plugin.prepare(SAMPLE_RATE);
+
+    let mut seed = 1u32;
+    let mut samples: Vec<[f32; 2]> = (0..NUM_SAMPLES)
+        .map(|_| {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            let x = seed as f32 / u32::MAX as f32 - 0.5;
+            [x, -x]
+        })
+        .collect();
+    process(&mut plugin, &mut samples);
+    samples
+}
+
+#[test]
+fn bypassing_a_stage_mid_chain_lowers_the_slope() {
+    assert_eq!(render(3, &[2]), render(2, &[]));
+}
+
+#[test]
+fn bypassing_every_stage_leaves_the_first_one() {
+    assert_eq!(render(2, &[1, 2]), render(1, &[]));
+}
+
+#[test]
+fn unbypassing_after_moving_the_cutoff_is_click_free() {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "slope", |_| 1. / (MAX_STAGES - 1) as f32);
+    set_param(&plugin, "cutoff", |_| 0.8);
+    plugin.prepare(SAMPLE_RATE);
+
+    // a sine far below the cutoff, each stage barely touches it
+    let period = 8 * BLOCK_SIZE;
+    let amplitude = 0.5;
+    let sine = |n: usize| {
+        (0..n)
+            .map(|i| [amplitude * (2. * PI * i as f32 / period as f32).sin(); 2])
+            .collect::<Vec<_>>()
+    };
+
+    let mut samples = sine(period);
+    process(&mut plugin, &mut samples);
+
+    set_param(&plugin, "on_2", |_| 0.);
+    automate(&plugin, "cutoff", 0.9);
+    let mut samples = sine(period);
+    process(&mut plugin, &mut samples);
+    let last = samples[period - 1][0];
+
+    set_param(&plugin, "on_2", |_| 1.);
+    let mut samples = sine(period);
+    process(&mut plugin, &mut samples);
+
+    // the sine's own largest step, a click would be orders of magnitude above it
+    let sine_step = amplitude * 2. * PI / period as f32;
+    let mut previous = last;
+    for (i, sample) in samples.iter().enumerate() {
+        let step = (sample[0] - previous).abs();
+        assert!(step < 4. * sine_step, "sample {i}: jumped by {step}");
+        previous = sample[0];
+    }
+}
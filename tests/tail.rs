@@ -0,0 +1,59 @@
+//! Once the input goes silent, the host is told how long the resonance keeps
+//! ringing, so that it doesn't cut the plugin off early, and that it can suspend it
+//! once the output has died out
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{automate, process, set_param, SAMPLE_RATE};
+
+fn plugin(cutoff: f32, resonance: f32) -> OnePoleFilter {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "cutoff", |_| cutoff);
+    set_param(&plugin, "cutoff_r", |_| cutoff);
+    set_param(&plugin, "resonance", |_| resonance);
+    plugin.prepare(SAMPLE_RATE);
+    plugin
+}
+
+fn tail(cutoff: f32, resonance: f32) -> u32 {
+    plugin(cutoff, resonance).tail()
+}
+
+#[test]
+fn no_tail_without_resonance() {
+    assert_eq!(tail(0.5, 0.), 0);
+}
+
+#[test]
+fn more_resonance_rings_longer() {
+    let (low, high) = (tail(0.5, 0.3), tail(0.5, 1.));
+    assert!(0 < low && low < high, "{low} samples, then {high}");
+}
+
+#[test]
+fn lower_cutoffs_ring_longer() {
+    let (low, high) = (tail(0.1, 0.5), tail(0.9, 0.5));
+    assert!(low > high, "{low} samples, then {high}");
+}
+
+#[test]
+fn quieter_outputs_are_closer_to_silence() {
+    let plugin = plugin(0.5, 0.5);
+    let loud = plugin.remaining_tail(1.).unwrap();
+    let quiet = plugin.remaining_tail(0.01).unwrap();
+    assert!(quiet < loud, "{quiet} samples, then {loud}");
+    assert_eq!(plugin.remaining_tail(0.), Some(0));
+}
+
+#[test]
+fn the_tail_waits_for_the_smoothers() {
+    let mut plugin = plugin(0.5, 0.);
+    automate(&plugin, "cutoff", 0.1);
+    assert_eq!(plugin.remaining_tail(0.), None);
+
+    // well past the smoothers' glide
+    let mut samples = vec![[0.; 2]; SAMPLE_RATE as usize / 10];
+    process(&mut plugin, &mut samples);
+    assert_eq!(plugin.remaining_tail(0.), Some(0));
+}
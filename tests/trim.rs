@@ -0,0 +1,48 @@
+//! The trims scale each channel's input on its own, with the filters being linear,
+//! each channel's output must be scaled by just as much
+
+use nih_plug::prelude::*;
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{process, set_param, SAMPLE_RATE};
+
+const NUM_SAMPLES: usize = 4096;
+/// in dB, of the left and right channels, both within the trims' `-12..=12` range
+const TRIMS: [f32; 2] = [6., -6.];
+
+fn render(trims: [f32; 2]) -> Vec<[f32; 2]> {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "trim_l", |_| (trims[0] + 12.) / 24.);
+    set_param(&plugin, "trim_r", |_| (trims[1] + 12.) / 24.);
+    plugin.prepare(SAMPLE_RATE);
+
+    let mut seed = 1u32;
+    let mut samples: Vec<[f32; 2]> = (0..NUM_SAMPLES)
+        .map(|_| {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            let x = 0.1 * (seed as f32 / u32::MAX as f32 - 0.5);
+            [x, x]
+        })
+        .collect();
+    process(&mut plugin, &mut samples);
+    samples
+}
+
+#[test]
+fn trims_scale_each_channel() {
+    let gains = TRIMS.map(util::db_to_gain);
+    let untrimmed = render([0.; 2]);
+    let trimmed = render(TRIMS);
+
+    for (i, (a, b)) in trimmed.iter().zip(&untrimmed).enumerate() {
+        for lane in 0..2 {
+            let expected = b[lane] * gains[lane];
+            assert!(
+                (a[lane] - expected).abs() < 1e-5,
+                "sample {i}, lane {lane}: {}, expected {expected}",
+                a[lane],
+            );
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//! The true peak limiter must keep peaks falling between samples under the
+//! ceiling, not just the samples themselves
+
+use core::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{process, set_param, SAMPLE_RATE};
+
+const NUM_SAMPLES: usize = 16384;
+
+/// -6 dB
+const CEILING: f32 = 0.5;
+
+/// At this frequency, peaks falling between the detector's upsampled samples
+/// are missed by up to 2%
+const TOLERANCE: f32 = 1.03;
+
+#[test]
+fn inter_sample_peaks_stay_under_the_ceiling() {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "cutoff", |_| 1.);
+    set_param(&plugin, "ceiling_mode", |_| 1.);
+    set_param(&plugin, "ceiling", |_| 0.75);
+    plugin.prepare(SAMPLE_RATE);
+
+    // a sine at a quarter of the sample rate, sampled halfway between its peaks
+    // and zero crossings, its samples are 3 dB below its true peak level
+    let mut samples: Vec<[f32; 2]> = (0..NUM_SAMPLES)
+        .map(|i| {
+            let x = (i as f32 * FRAC_PI_2 + FRAC_PI_4).sin();
+            [x, x]
+        })
+        .collect();
+    process(&mut plugin, &mut samples);
+
+    // once everything's settled, two consecutive samples
+    // are a quarter turn apart, giving away the amplitude
+    for (i, pair) in samples[NUM_SAMPLES / 2..].windows(2).enumerate() {
+        for (lane, (a, b)) in pair[0].iter().zip(pair[1]).enumerate() {
+            let peak = a.hypot(b);
+            assert!(
+                peak < CEILING * TOLERANCE,
+                "sample {i}, lane {lane}: true peak at {peak}",
+            );
+        }
+    }
+}
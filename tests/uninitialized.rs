@@ -0,0 +1,26 @@
+//! Some hosts process before initializing the plugin, when scanning it, it should
+//! then behave as if it had been initialized at 44.1 kHz
+
+use core::f32::consts::PI;
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{process, SAMPLE_RATE};
+
+fn render(plugin: &mut OnePoleFilter) -> Vec<[f32; 2]> {
+    let w = 2. * PI * 1000. / SAMPLE_RATE;
+    let mut samples: Vec<[f32; 2]> = (0..4410).map(|i| [(w * i as f32).sin(); 2]).collect();
+    process(plugin, &mut samples);
+    samples
+}
+
+#[test]
+fn processes_before_initializing() {
+    let uninitialized = render(&mut OnePoleFilter::default());
+    assert!(uninitialized.iter().flatten().all(|x| x.is_finite()));
+
+    let mut initialized = OnePoleFilter::default();
+    initialized.prepare(SAMPLE_RATE);
+    assert!(uninitialized == render(&mut initialized));
+}
@@ -0,0 +1,53 @@
+//! Feeds a sine through the filter, with and without warmth, which must add a
+//! second harmonic, without leaving any DC behind
+
+use core::f32::consts::TAU;
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{process, set_param, SAMPLE_RATE};
+
+const FREQ: f32 = 200.;
+const AMPLITUDE: f32 = 0.5;
+/// one second, the second half of it is measured, once the DC blocker has settled
+const NUM_SAMPLES: usize = SAMPLE_RATE as usize;
+
+/// Mean of the left channel's output, and the amplitude of its second harmonic
+fn dc_and_second_harmonic(warmth: f32) -> (f32, f32) {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "warmth", |_| warmth);
+    plugin.prepare(SAMPLE_RATE);
+
+    let mut samples: Vec<[f32; 2]> = (0..NUM_SAMPLES)
+        .map(|i| [AMPLITUDE * (TAU * FREQ * i as f32 / SAMPLE_RATE).sin(); 2])
+        .collect();
+    process(&mut plugin, &mut samples);
+
+    let settled = &samples[NUM_SAMPLES / 2..];
+    let len = settled.len() as f32;
+    let dc = settled.iter().map(|s| s[0]).sum::<f32>() / len;
+
+    let (re, im) = settled
+        .iter()
+        .enumerate()
+        .fold((0., 0.), |(re, im), (i, s)| {
+            let phase = TAU * 2. * FREQ * i as f32 / SAMPLE_RATE;
+            (re + s[0] * phase.cos(), im + s[0] * phase.sin())
+        });
+    (dc, 2. * (re * re + im * im).sqrt() / len)
+}
+
+#[test]
+fn warmth_adds_a_second_harmonic() {
+    let (_, clean) = dc_and_second_harmonic(0.);
+    let (_, warm) = dc_and_second_harmonic(1.);
+    assert!(clean < 1e-4, "second harmonic at {clean} without warmth");
+    assert!(warm > 1e-2, "second harmonic at {warm} with warmth");
+}
+
+#[test]
+fn warmth_leaves_no_dc() {
+    let (dc, _) = dc_and_second_harmonic(1.);
+    assert!(dc.abs() < 1e-3, "output offset by {dc}");
+}
@@ -0,0 +1,59 @@
+//! The width, and the mid/side processing, only apply to the front left and right pair
+
+use one_pole::OnePoleFilter;
+
+mod common;
+use common::{set_param, SAMPLE_RATE};
+
+const NUM_SAMPLES: usize = 4096;
+
+/// A plugin with the width, out of `0..=2`, at `width`
+fn plugin(width: f32, ms_mode: bool) -> OnePoleFilter {
+    let mut plugin = OnePoleFilter::default();
+    set_param(&plugin, "width", |_| width / 2.);
+    set_param(&plugin, "ms_mode", |_| if ms_mode { 1. } else { 0. });
+    plugin.prepare(SAMPLE_RATE);
+    plugin
+}
+
+/// `num_channels` channels, each a sine of its own frequency, processed by `plugin`
+fn render(mut plugin: OnePoleFilter, num_channels: usize) -> Vec<Vec<f32>> {
+    let mut channels: Vec<Vec<f32>> = (0..num_channels)
+        .map(|c| {
+            let w = 0.01 * (c + 1) as f32;
+            (0..NUM_SAMPLES)
+                .map(|i| 0.5 * (w * i as f32).sin())
+                .collect()
+        })
+        .collect();
+    let mut slices: Vec<&mut [f32]> = channels.iter_mut().map(Vec::as_mut_slice).collect();
+    plugin.process_channels(&mut slices);
+    channels
+}
+
+#[test]
+fn mono_is_left_alone() {
+    let reference = render(plugin(1., false), 1);
+    for ms_mode in [false, true] {
+        for width in [0., 2.] {
+            let output = render(plugin(width, ms_mode), 1);
+            assert_eq!(output, reference, "width {width}, mid/side {ms_mode}");
+        }
+    }
+}
+
+#[test]
+fn only_the_front_pair_narrows() {
+    let reference = render(plugin(1., false), 4);
+    let output = render(plugin(0., false), 4);
+    let [l, r] = [&output[0], &output[1]];
+    assert!(l.iter().zip(r).all(|(l, r)| (l - r).abs() < 1e-6));
+    assert_eq!(output[2..], reference[2..]);
+}
+
+#[test]
+fn only_the_front_pair_is_encoded() {
+    let reference = render(plugin(1., false), 4);
+    let output = render(plugin(1., true), 4);
+    assert_eq!(output[2..], reference[2..]);
+}